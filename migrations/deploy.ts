@@ -8,5 +8,23 @@ module.exports = async function (provider) {
   // Configure client to use the provider.
   anchor.setProvider(provider);
 
-  // Add your deploy script here.
+  // Global info written by an older program is shorter than the current layout and keeps
+  // total_supply in base units, migrate_global_info reallocs it and converts it to whole tokens.
+  // Old pools are migrated one by one with migrate_token_pools.
+  const program = anchor.workspace.TokenMinter;
+  const [globalInfo] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("global_info")],
+    program.programId
+  );
+  const info = await provider.connection.getAccountInfo(globalInfo);
+  if (info && info.data.length < program.account.globalInfo.size) {
+    await program.methods
+      .migrateGlobalInfo()
+      .accounts({
+        globalInfo,
+        admin: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
 };
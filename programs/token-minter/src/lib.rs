@@ -1,15 +1,31 @@
 // 1. Import dependencies
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{self, AssociatedToken},
     metadata::{
-        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
-        Metadata as Metaplex,
+        create_metadata_accounts_v3,
+        mpl_token_metadata::types::{Collection, Creator, DataV2},
+        sign_metadata, update_metadata_accounts_v2, verify_sized_collection_item,
+        CreateMetadataAccountsV3, Metadata as Metaplex, MetadataAccount, SignMetadata,
+        UpdateMetadataAccountsV2, VerifySizedCollectionItem,
     },
-    token::{self, mint_to, Mint, MintTo, Token, TokenAccount},
+    token::{self, Token},
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            metadata_pointer,
+            transfer_fee::{self, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+    },
+    token_2022::Token2022,
+    token_interface::{self, mint_to, Mint, MintTo, TokenAccount, TokenInterface},
 };
-use solana_program::program::invoke;
+use solana_program::keccak::hashv;
+use solana_program::program::{invoke, invoke_signed};
 use solana_program::system_instruction::transfer;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
 
 // 2. Declare Program ID (SolPG will automatically update this when you deploy)
 declare_id!("6Lss5AKnmkRPg7fmfRDXWMszrxcVzj9fEf9BH7iwBcQ2");
@@ -18,21 +34,301 @@ pub const GLOBAL_INFO_SEED: &str = "global_info";
 pub const TOKEN_POOL_SEED: &str = "token_pool";
 pub const SOL_VAULT_SEED: &str = "sol_escrow_seed";
 pub const MINT_SEED: &str = "mint";
+pub const BUYER_INFO_SEED: &str = "buyer_info";
+pub const AUCTION_COMMITMENT_SEED: &str = "auction_commitment";
+pub const QUOTE_VAULT_SEED: &str = "quote_vault";
+pub const TRANSFER_FEE_AUTHORITY_SEED: &str = "transfer_fee_authority";
+pub const TRANSFER_FEE_VAULT_SEED: &str = "transfer_fee_vault";
+pub const NAME_RECORD_SEED: &str = "name_record";
+pub const LAUNCH_SEED: &str = "launch";
+pub const CREATOR_INFO_SEED: &str = "creator_info";
+pub const COLLECTION_AUTHORITY_SEED: &str = "collection_authority";
+pub const LAUNCH_PROFILE_SEED: &str = "launch_profile";
+pub const VESTING_SEED: &str = "vesting";
+pub const VESTING_VAULT_SEED: &str = "vesting_vault";
+pub const AIRDROP_SEED: &str = "airdrop";
+pub const AIRDROP_VAULT_SEED: &str = "airdrop_vault";
+pub const POSITION_SEED: &str = "position";
+pub const CANDLES_SEED: &str = "candles";
+
+pub const MAX_QUOTE_MINTS: usize = 8;
+
+pub const CANDLE_INTERVAL: i64 = 60; // seconds
+pub const CANDLE_COUNT: usize = 180; // 3 hours of 1 minute candles, keeps the account under the CPI size limit
+
+pub const NAME_RESERVATION_SECONDS: i64 = 7 * 24 * 60 * 60;
+pub const NAME_RESERVATION_COOLDOWN_SECONDS: i64 = 7 * 24 * 60 * 60; // after a lapse, for its last holder
+
+pub const MAX_DESCRIPTION_LEN: usize = 512;
+pub const MAX_LINK_LEN: usize = 128;
+
+pub const LAUNCH_MODE_CURVE: u8 = 0;
+pub const LAUNCH_MODE_BATCH_AUCTION: u8 = 1;
+pub const LAUNCH_MODE_DUTCH_AUCTION: u8 = 2;
+
+pub const DUTCH_DECAY_LINEAR: u8 = 0;
+pub const DUTCH_DECAY_EXPONENTIAL: u8 = 1;
+
+pub const LAUNCH_STATUS_ACTIVE: u8 = 0;
+pub const LAUNCH_STATUS_GRADUATED: u8 = 1;
+pub const LAUNCH_STATUS_CANCELLED: u8 = 2;
+
+// Layout versions, accounts written before versioning read as 0, see the migrate instructions
+pub const GLOBAL_INFO_VERSION: u8 = 1;
+pub const TOKEN_POOL_VERSION: u8 = 1;
 
 pub const GLOBAL_INFO_SIZE: usize = 8 + std::mem::size_of::<GlobalInfo>() + 8;
 pub const TOKEN_POOL_SIZE: usize = 8 + std::mem::size_of::<TokenPools>() + 8;
+pub const SOL_ESCROW_SIZE: usize = 8 + std::mem::size_of::<SolEscrow>() + 8;
+pub const BUYER_INFO_SIZE: usize = 8 + std::mem::size_of::<BuyerInfo>() + 8;
+pub const AUCTION_COMMITMENT_SIZE: usize = 8 + std::mem::size_of::<AuctionCommitment>() + 8;
+pub const CANDLE_BUFFER_SIZE: usize = 8 + std::mem::size_of::<CandleBuffer>() + 8;
+pub const POSITION_SIZE: usize = 8 + std::mem::size_of::<Position>() + 8;
+pub const VESTING_SIZE: usize = 8 + std::mem::size_of::<CreatorVesting>() + 8;
+// Base size, create_token adds one bit per airdrop leaf
+pub const AIRDROP_SIZE: usize = 8 + std::mem::size_of::<Airdrop>() + 8;
+pub const NAME_RECORD_SIZE: usize = 8 + std::mem::size_of::<NameRecord>() + 8;
+pub const LAUNCH_RECORD_SIZE: usize = 8 + std::mem::size_of::<LaunchRecord>() + 8;
+pub const CREATOR_INFO_SIZE: usize = 8 + std::mem::size_of::<CreatorInfo>() + 8;
+// Strings are stored inline up to their max length, size_of covers their length prefixes
+pub const LAUNCH_PROFILE_SIZE: usize =
+    8 + std::mem::size_of::<LaunchProfile>() + MAX_DESCRIPTION_LEN + 3 * MAX_LINK_LEN + 8;
 
 pub fn calculate_fee(amount: u64, fee_percent: u32) -> u64 {
     (amount * fee_percent as u64) / 10000
 }
 
-pub fn get_price(sol_reserve: u64, token_reserve: u64) -> u64 {
-    // Multiply sol_reserve by 1_000_000_000 (1e9 in integer form) to avoid floating-point operations
-    let sol_reserve_scaled = sol_reserve as u128 * 1_000_000_000u128;
+// a * b / c without intermediate overflow, errors instead of truncating the result or dividing by zero
+pub fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    let quotient = (a as u128 * b as u128)
+        .checked_div(c as u128)
+        .ok_or(CustomError::MathOverflow)?;
+    Ok(u64::try_from(quotient).map_err(|_| CustomError::MathOverflow)?)
+}
+
+pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64> {
+    let product = a as u128 * b as u128;
+    let quotient = product
+        .checked_div(c as u128)
+        .ok_or(CustomError::MathOverflow)?;
+    let quotient = if quotient * (c as u128) < product {
+        quotient + 1
+    } else {
+        quotient
+    };
+    Ok(u64::try_from(quotient).map_err(|_| CustomError::MathOverflow)?)
+}
+
+// Moves quote from a user into the pool: lamports into the escrow, or SPL tokens into the quote vault
+pub fn deposit_quote<'info>(
+    user: AccountInfo<'info>,
+    escrow_account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    quote: Option<QuoteTokenAccounts<'info>>,
+    amount: u64,
+) -> Result<()> {
+    match quote {
+        Some(quote) => token::transfer(
+            CpiContext::new(
+                quote.token_program,
+                token::Transfer {
+                    from: quote.user_account,
+                    to: quote.vault,
+                    authority: user,
+                },
+            ),
+            amount,
+        ),
+        None => {
+            let transfer_instruction = transfer(&user.key(), &escrow_account.key(), amount);
+            invoke(
+                &transfer_instruction,
+                &[user, escrow_account, system_program],
+            )?;
+            Ok(())
+        }
+    }
+}
+
+// Moves quote out of the pool to a user, the quote vault signs with its own seeds
+pub fn withdraw_quote<'info>(
+    escrow_account: &Account<'info, SolEscrow>,
+    recipient: AccountInfo<'info>,
+    quote: Option<QuoteTokenAccounts<'info>>,
+    vault_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    match quote {
+        Some(quote) => token::transfer(
+            CpiContext::new_with_signer(
+                quote.token_program,
+                token::Transfer {
+                    from: quote.vault.clone(),
+                    to: quote.user_account,
+                    authority: quote.vault,
+                },
+                &[vault_seeds],
+            ),
+            amount,
+        ),
+        None => {
+            require!(
+                amount <= escrow_balance(escrow_account),
+                CustomError::InvalidSolAmount
+            );
+            **escrow_account.to_account_info().try_borrow_mut_lamports()? -= amount;
+            **recipient.try_borrow_mut_lamports()? += amount;
+            Ok(())
+        }
+    }
+}
+
+// Grows a program account to new_len, the payer tops up its rent first
+pub fn realloc_with_rent<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    system: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    account.realloc(new_len, false)?;
+    Ok(())
+}
+
+// Lamports in the escrow above its rent reserve, the only part that can be paid out
+pub fn escrow_balance(escrow_account: &Account<SolEscrow>) -> u64 {
+    escrow_account
+        .to_account_info()
+        .lamports()
+        .saturating_sub(escrow_account.rent_reserve)
+}
+
+// Tokens the Token-2022 transfer-fee extension withholds when moving `amount`, 0 for other mints
+pub fn withheld_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Ok(config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?),
+        Err(_) => Ok(0),
+    }
+}
+
+// Creates a program derived account the way Anchor's init does. PDAs are predictable, so anyone
+// can send lamports to one ahead of time, which makes create_account fail. Those accounts are
+// topped up to the rent minimum, allocated and assigned instead.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system: &AccountInfo<'info>,
+    lamports: u64,
+    space: usize,
+    owner: &Pubkey,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let current_lamports = account.lamports();
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                signer,
+            ),
+            lamports,
+            space as u64,
+            owner,
+        );
+    }
+    let top_up = lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system.clone(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system.clone(),
+            system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            signer,
+        ),
+        owner,
+    )
+}
+
+pub fn get_price(sol_reserve: u64, token_reserve: u64, decimals: u8) -> Result<u64> {
+    // Price of one whole token, scaling by 10^decimals avoids floating-point operations
+    let sol_reserve_scaled = sol_reserve as u128 * 10u128.pow(decimals as u32);
     let token_reserve_scaled = token_reserve as u128;
 
-    // Perform the division and cast back to u64
-    (sol_reserve_scaled / token_reserve_scaled) as u64
+    // An empty token reserve has no price, fail the instruction rather than panic
+    let price = sol_reserve_scaled
+        .checked_div(token_reserve_scaled)
+        .ok_or(CustomError::MathOverflow)?;
+    Ok(u64::try_from(price).map_err(|_| CustomError::MathOverflow)?)
+}
+
+// Case-insensitive key of the name registry, also keeps seeds of long names within 32 bytes
+pub fn name_hash(name: &str) -> [u8; 32] {
+    hashv(&[name.to_lowercase().as_bytes()]).0
+}
+
+// Allowlist leaves are keccak(wallet || allocation)
+pub fn verify_allowlist_proof(root: [u8; 32], wallet: &Pubkey, proof: &AllowlistProof) -> bool {
+    let leaf = hashv(&[wallet.as_ref(), &proof.allocation.to_le_bytes()]).0;
+    verify_merkle_proof(root, leaf, &proof.proof)
+}
+
+// Sorted-pair keccak merkle proof
+pub fn verify_merkle_proof(root: [u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let mut node = leaf;
+    for sibling in proof.iter() {
+        node = if node <= *sibling {
+            hashv(&[&node, sibling]).0
+        } else {
+            hashv(&[sibling, &node]).0
+        };
+    }
+    node == root
 }
 // 3. Define the program and instructions
 #[program]
@@ -43,9 +339,140 @@ mod token_minter {
         ctx.accounts.global_info.fee_percent = 300; // 1% = 100
         ctx.accounts.global_info.target_market_cap = 69000;
         ctx.accounts.global_info.target_lp_amount = 12000;
-        ctx.accounts.global_info.total_supply = 1e18 as u64;
+        ctx.accounts.global_info.total_supply = 1e9 as u64; // whole tokens
         ctx.accounts.global_info.initial_amount = 20e9 as u64;
+        ctx.accounts.global_info.sniper_window_seconds = 60;
+        ctx.accounts.global_info.max_buy_amount = 0; // 0 -> no lamport cap
+        ctx.accounts.global_info.max_buy_percent = 200; // 2% of supply
+        ctx.accounts.global_info.creator_fee_share = 5000; // 50% of harvested transfer fees
+        ctx.accounts.global_info.max_creator_allocation = 1000; // 10% of supply
         ctx.accounts.global_info.owner = ctx.accounts.owner.key();
+        ctx.accounts.global_info.version = GLOBAL_INFO_VERSION;
+        Ok(())
+    }
+
+    // Upgrades a global_info written by an older program in place. Fields were only ever
+    // appended, so the old bytes stay valid and the new ones read as zero after the realloc.
+    pub fn migrate_global_info(ctx: Context<MigrateGlobalInfo>) -> Result<()> {
+        let info = ctx.accounts.global_info.to_account_info();
+        if info.data_len() < GLOBAL_INFO_SIZE {
+            realloc_with_rent(
+                &info,
+                GLOBAL_INFO_SIZE,
+                &ctx.accounts.admin.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+        let mut global_info = GlobalInfo::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        require!(
+            global_info.version < GLOBAL_INFO_VERSION,
+            CustomError::AlreadyMigrated
+        );
+        if global_info.version == 0 {
+            // total_supply used to be base units of a 9 decimals mint, it is now whole tokens
+            global_info.total_supply /= 1_000_000_000;
+            // Same defaults as initialize for everything added since
+            global_info.sniper_window_seconds = 60;
+            global_info.max_buy_percent = 200;
+            global_info.creator_fee_share = 5000;
+            global_info.max_creator_allocation = 1000;
+        }
+        global_info.version = GLOBAL_INFO_VERSION;
+        global_info.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    // Upgrades a pool created by an older program, along with its escrow, and gives it the
+    // launch record, creator entry and candles newer instructions expect. Old launches used
+    // name seeded mints, so the launch indices they counted are free for their records. Those
+    // mints are kept, update_metadata derives the mint from the launch index and cannot sign
+    // for them, their metadata stays as it was minted.
+    pub fn migrate_token_pools(
+        ctx: Context<MigrateTokenPools>,
+        launch_index: u32,
+        creator: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        require!(
+            ctx.accounts.global_info.version == GLOBAL_INFO_VERSION,
+            CustomError::NotMigrated
+        );
+        require!(
+            launch_index < ctx.accounts.global_info.token_count,
+            CustomError::InvalidLaunchIndex
+        );
+        let admin = ctx.accounts.admin.to_account_info();
+        let system = ctx.accounts.system_program.to_account_info();
+        let mint_key = ctx.accounts.mint.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        let info = ctx.accounts.token_pools.to_account_info();
+        if info.data_len() < TOKEN_POOL_SIZE {
+            realloc_with_rent(&info, TOKEN_POOL_SIZE, &admin, &system)?;
+        }
+        let mut token_pools = TokenPools::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            token_pools.version < TOKEN_POOL_VERSION,
+            CustomError::AlreadyMigrated
+        );
+        if token_pools.version == 0 {
+            // Old pools are native SOL bonding curves whose fees stayed in sol_reserve
+            token_pools.snapshot_config(&ctx.accounts.global_info);
+            token_pools.decimals = ctx.accounts.mint.decimals;
+            token_pools.total_supply = ctx.accounts.mint.supply;
+            token_pools.quote_decimals = 9;
+            token_pools.virtual_sol_reserve = ctx.accounts.global_info.initial_amount;
+            token_pools.creator = creator;
+            token_pools.launch_index = launch_index;
+            token_pools.accumulate_price(now);
+            token_pools.last_price = token_pools.spot_price()?;
+            token_pools.twap_checkpoint_at = now;
+        }
+        token_pools.version = TOKEN_POOL_VERSION;
+        token_pools.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        // Old escrows are bare lamport holders, the rent of the new layout stays in reserve
+        let escrow = ctx.accounts.escrow_account.to_account_info();
+        if escrow.data_len() < SOL_ESCROW_SIZE {
+            realloc_with_rent(&escrow, SOL_ESCROW_SIZE, &admin, &system)?;
+            SolEscrow {
+                mint: mint_key,
+                rent_reserve: Rent::get()?.minimum_balance(SOL_ESCROW_SIZE),
+            }
+            .try_serialize(&mut &mut escrow.try_borrow_mut_data()?[..])?;
+        }
+
+        let launch = &mut ctx.accounts.launch;
+        launch.index = launch_index;
+        launch.mint = mint_key;
+        launch.creator = creator;
+        launch.created_at = now;
+        launch.status = if token_pools.launched == 1 {
+            LAUNCH_STATUS_GRADUATED
+        } else {
+            LAUNCH_STATUS_ACTIVE
+        };
+
+        realloc_with_rent(
+            &ctx.accounts.creator_info.to_account_info(),
+            CREATOR_INFO_SIZE + 32 * (ctx.accounts.creator_info.mints.len() + 1),
+            &admin,
+            &system,
+        )?;
+        let creator_info = &mut ctx.accounts.creator_info;
+        creator_info.creator = creator;
+        creator_info.launch_count += 1;
+        creator_info.graduated_count += token_pools.launched as u32;
+        creator_info.mints.push(mint_key);
+
+        ctx.accounts.candles.load_init()?.mint = mint_key;
         Ok(())
     }
 
@@ -54,65 +481,550 @@ mod token_minter {
         metadata: InitTokenParams,
         amount: u64,
     ) -> Result<()> {
-        require!(
-            amount < ctx.accounts.global_info.initial_amount,
-            CustomError::InvalidInitialValue
-        );
-        ctx.accounts.token_pools.sol_reserve = ctx.accounts.global_info.initial_amount;
-        ctx.accounts.token_pools.token_reserve = ctx.accounts.global_info.total_supply;
+        // Quote-token launches use the virtual reserve configured for their quote mint
+        let initial_amount = match &ctx.accounts.quote_mint {
+            Some(quote_mint) => {
+                let quote_config = ctx
+                    .accounts
+                    .global_info
+                    .quote_mints
+                    .iter()
+                    .find(|q| q.mint == quote_mint.key())
+                    .ok_or(CustomError::QuoteMintNotAllowed)?;
+                ctx.accounts.token_pools.quote_mint = quote_mint.key();
+                ctx.accounts.token_pools.quote_decimals = quote_mint.decimals;
+                ctx.accounts.token_pools.quote_usd_pegged = quote_config.usd_pegged;
+                ctx.accounts.token_pools.quote_max_buy_amount = quote_config.max_buy_amount;
+                quote_config.initial_amount
+            }
+            None => {
+                ctx.accounts.token_pools.quote_decimals = 9;
+                ctx.accounts.global_info.initial_amount
+            }
+        };
+        require!(amount < initial_amount, CustomError::InvalidInitialValue);
+
+        // Supply is configured in whole tokens, the pool keeps it in base units of this mint
+        let total_supply = 10u64
+            .checked_pow(metadata.decimals as u32)
+            .and_then(|unit| ctx.accounts.global_info.total_supply.checked_mul(unit))
+            .ok_or(CustomError::SupplyOverflow)?;
+        ctx.accounts.token_pools.decimals = metadata.decimals;
+        ctx.accounts.token_pools.total_supply = total_supply;
+
+        // The creator allocation is held in a vault of the vesting PDA until it vests, off the curve
+        let vesting_amount = match &metadata.vesting {
+            Some(params) => {
+                require!(
+                    params.amount
+                        <= mul_div(
+                            total_supply,
+                            ctx.accounts.global_info.max_creator_allocation as u64,
+                            10000
+                        )?,
+                    CustomError::InvalidVestingParams
+                );
+                require!(
+                    params.cliff_seconds >= 0 && params.duration_seconds >= params.cliff_seconds,
+                    CustomError::InvalidVestingParams
+                );
+                let now = Clock::get()?.unix_timestamp;
+                let vesting = ctx
+                    .accounts
+                    .vesting
+                    .as_mut()
+                    .ok_or(CustomError::MissingVestingAccount)?;
+                vesting.beneficiary = ctx.accounts.payer.key();
+                vesting.mint = ctx.accounts.mint.key();
+                vesting.total_amount = params.amount;
+                vesting.start_at = now;
+                vesting.cliff_at = now + params.cliff_seconds;
+                vesting.end_at = now + params.duration_seconds;
+                params.amount
+            }
+            None => 0,
+        };
+
+        // Airdropped tokens get a vault of the airdrop PDA too, unclaimed ones are burned on expiry
+        let airdrop_amount = match &metadata.airdrop {
+            Some(params) => {
+                require!(
+                    params.amount > 0
+                        && params.leaf_count > 0
+                        && params.expires_at > Clock::get()?.unix_timestamp,
+                    CustomError::InvalidAirdropParams
+                );
+                let airdrop = ctx
+                    .accounts
+                    .airdrop
+                    .as_mut()
+                    .ok_or(CustomError::MissingAirdropAccount)?;
+                airdrop.mint = ctx.accounts.mint.key();
+                airdrop.merkle_root = params.merkle_root;
+                airdrop.total_amount = params.amount;
+                airdrop.expires_at = params.expires_at;
+                airdrop.claimed_bitmap = vec![0; params.leaf_count as usize / 8 + 1];
+                params.amount
+            }
+            None => 0,
+        };
+        let curve_supply = total_supply
+            .checked_sub(vesting_amount + airdrop_amount)
+            .ok_or(CustomError::InvalidAirdropParams)?;
+        ctx.accounts.token_pools.sol_reserve = initial_amount;
+        ctx.accounts.token_pools.virtual_sol_reserve = initial_amount;
+        ctx.accounts.escrow_account.mint = ctx.accounts.mint.key();
+        ctx.accounts.escrow_account.rent_reserve = ctx.accounts.escrow_account.get_lamports();
+        ctx.accounts.token_pools.token_reserve = curve_supply;
+        // A start in the past means trading opens at creation
+        ctx.accounts.token_pools.trading_starts_at =
+            metadata.trading_starts_at.max(Clock::get()?.unix_timestamp);
+        // Launches that trade immediately can only get an allowlist here, set_allowlist is too late
+        if let Some(allowlist) = &metadata.allowlist {
+            require!(
+                allowlist.ends_at >= ctx.accounts.token_pools.trading_starts_at,
+                CustomError::InvalidAllowlistEnd
+            );
+            ctx.accounts.token_pools.allowlist_root = allowlist.root;
+            ctx.accounts.token_pools.allowlist_ends_at = allowlist.ends_at;
+        }
+        ctx.accounts.token_pools.creator = ctx.accounts.payer.key();
+        ctx.accounts.token_pools.version = TOKEN_POOL_VERSION;
+        // Later admin changes only reach this pool if its creator adopts them
+        ctx.accounts
+            .token_pools
+            .snapshot_config(&ctx.accounts.global_info);
 
-        let name = metadata.name.clone();
-        let seeds = &[MINT_SEED.as_bytes(), name.as_bytes(), &[ctx.bumps.mint]];
+        // Every mode prices off the curve at some point, a zero start price would divide by zero
+        let floor_price = get_price(initial_amount, curve_supply, metadata.decimals)?;
+        require!(floor_price > 0, CustomError::ZeroStartPrice);
+
+        if let Some(auction) = &metadata.batch_auction {
+            require!(amount == 0, CustomError::InitialBuyNotAllowed);
+            // Part of the curve supply must stay in the reserve to price the curve after settling
+            require!(
+                auction.supply > 0 && auction.supply < curve_supply,
+                CustomError::InvalidAuctionParams
+            );
+            require!(
+                auction.ends_at > metadata.trading_starts_at
+                    && auction.ends_at > Clock::get()?.unix_timestamp,
+                CustomError::InvalidAuctionParams
+            );
+            require!(
+                auction.max_price == 0 || auction.max_price >= floor_price,
+                CustomError::InvalidAuctionParams
+            );
+            ctx.accounts.token_pools.launch_mode = LAUNCH_MODE_BATCH_AUCTION;
+            ctx.accounts.token_pools.auction_ends_at = auction.ends_at;
+            ctx.accounts.token_pools.auction_supply = auction.supply;
+            ctx.accounts.token_pools.auction_max_price = auction.max_price;
+        }
+
+        if let Some(auction) = &metadata.dutch_auction {
+            require!(
+                metadata.batch_auction.is_none(),
+                CustomError::InvalidLaunchMode
+            );
+            require!(amount == 0, CustomError::InitialBuyNotAllowed);
+            // Same as batch auctions, a sell-out must leave the curve something to price
+            require!(
+                auction.supply > 0 && auction.supply < curve_supply,
+                CustomError::InvalidAuctionParams
+            );
+            require!(
+                auction.floor_price > 0 && auction.start_price >= auction.floor_price,
+                CustomError::InvalidAuctionParams
+            );
+            require!(
+                auction.end_slot > auction.start_slot,
+                CustomError::InvalidAuctionParams
+            );
+            require!(
+                auction.decay == DUTCH_DECAY_LINEAR
+                    || (auction.decay == DUTCH_DECAY_EXPONENTIAL && auction.decay_rate < 10000),
+                CustomError::InvalidAuctionParams
+            );
+            ctx.accounts.token_pools.launch_mode = LAUNCH_MODE_DUTCH_AUCTION;
+            ctx.accounts.token_pools.auction_supply = auction.supply;
+            ctx.accounts.token_pools.dutch_start_slot = auction.start_slot;
+            ctx.accounts.token_pools.dutch_start_price = auction.start_price;
+            ctx.accounts.token_pools.dutch_floor_price = auction.floor_price;
+            ctx.accounts.token_pools.dutch_decay = auction.decay;
+            ctx.accounts.token_pools.dutch_decay_rate = auction.decay_rate;
+            ctx.accounts.token_pools.dutch_end_slot = auction.end_slot;
+        }
+
+        // Creators who reserved the name bind it to this launch, a name can only be bound once.
+        // Launches without a record share names freely.
+        if let Some(name_record) = &mut ctx.accounts.name_record {
+            require!(
+                name_record.owner == ctx.accounts.payer.key(),
+                CustomError::NameReserved
+            );
+            require!(
+                name_record.mint == Pubkey::default(),
+                CustomError::NameAlreadyUsed
+            );
+            name_record.mint = ctx.accounts.mint.key();
+        }
+
+        ctx.accounts.candles.load_init()?.mint = ctx.accounts.mint.key();
+
+        // Registry entry for indexers paging through launches by index
+        let index = ctx.accounts.global_info.token_count;
+        ctx.accounts.launch.index = index;
+        ctx.accounts.launch.mint = ctx.accounts.mint.key();
+        ctx.accounts.launch.creator = ctx.accounts.payer.key();
+        ctx.accounts.launch.created_at = Clock::get()?.unix_timestamp;
+        ctx.accounts.launch.status = LAUNCH_STATUS_ACTIVE;
+        ctx.accounts.token_pools.launch_index = index;
+
+        // The creator's list grows by one mint per launch, the payer tops up its rent
+        realloc_with_rent(
+            &ctx.accounts.creator_info.to_account_info(),
+            CREATOR_INFO_SIZE + 32 * (ctx.accounts.creator_info.mints.len() + 1),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        ctx.accounts.creator_info.creator = ctx.accounts.payer.key();
+        ctx.accounts.creator_info.launch_count += 1;
+        ctx.accounts
+            .creator_info
+            .mints
+            .push(ctx.accounts.mint.key());
+
+        let launch_index = index.to_le_bytes();
+        let seeds = &[MINT_SEED.as_bytes(), &launch_index, &[ctx.bumps.mint]];
         let signer = [&seeds[..]];
+        let token_2022 = ctx.accounts.token_program.key() == spl_token_2022::ID;
+        require!(
+            metadata.transfer_fee.is_none() || token_2022,
+            CustomError::TransferFeeRequiresToken2022
+        );
 
-        let token_data: DataV2 = DataV2 {
-            name: metadata.name,
-            symbol: metadata.symbol,
-            uri: metadata.uri,
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
+        // Token-2022 mints carry their metadata on the mint itself through the metadata-pointer
+        // extension, the extra lamports cover the metadata the token program reallocs for
+        let (mut extensions, metadata_len) = if token_2022 {
+            let token_metadata = TokenMetadata {
+                name: metadata.name.clone(),
+                symbol: metadata.symbol.clone(),
+                uri: metadata.uri.clone(),
+                ..Default::default()
+            };
+            (
+                vec![ExtensionType::MetadataPointer],
+                token_metadata.tlv_size_of()?,
+            )
+        } else {
+            (vec![], 0)
         };
+        if metadata.transfer_fee.is_some() {
+            extensions.push(ExtensionType::TransferFeeConfig);
+        }
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)?;
+        create_pda_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.minimum_balance(mint_len + metadata_len),
+            mint_len,
+            &ctx.accounts.token_program.key(),
+            &signer,
+        )?;
+        if token_2022 {
+            invoke(
+                &metadata_pointer::instruction::initialize(
+                    &ctx.accounts.token_program.key(),
+                    &ctx.accounts.mint.key(),
+                    Some(ctx.accounts.mint.key()),
+                    Some(ctx.accounts.mint.key()),
+                )?,
+                &[ctx.accounts.mint.to_account_info()],
+            )?;
+        }
+        // Withheld fees can only be withdrawn by the program, the fee itself is fixed at launch
+        if let Some(fee) = &metadata.transfer_fee {
+            let (fee_authority, _) = Pubkey::find_program_address(
+                &[
+                    TRANSFER_FEE_AUTHORITY_SEED.as_bytes(),
+                    ctx.accounts.mint.key().as_ref(),
+                ],
+                ctx.program_id,
+            );
+            invoke(
+                &transfer_fee::instruction::initialize_transfer_fee_config(
+                    &ctx.accounts.token_program.key(),
+                    &ctx.accounts.mint.key(),
+                    None,
+                    Some(&fee_authority),
+                    fee.basis_points,
+                    fee.maximum_fee,
+                )?,
+                &[ctx.accounts.mint.to_account_info()],
+            )?;
+        }
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::InitializeMint2 {
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            metadata.decimals,
+            &ctx.accounts.mint.key(),
+            None,
+        )?;
+
+        if token_2022 {
+            invoke_signed(
+                &spl_token_metadata_interface::instruction::initialize(
+                    &ctx.accounts.token_program.key(),
+                    &ctx.accounts.mint.key(),
+                    &ctx.accounts.mint.key(),
+                    &ctx.accounts.mint.key(),
+                    &ctx.accounts.mint.key(),
+                    metadata.name,
+                    metadata.symbol,
+                    metadata.uri,
+                ),
+                &[ctx.accounts.mint.to_account_info()],
+                &signer,
+            )?;
+        } else {
+            let collection_mint = ctx.accounts.global_info.collection_mint;
+            let token_data: DataV2 = DataV2 {
+                name: metadata.name,
+                symbol: metadata.symbol,
+                uri: metadata.uri,
+                seller_fee_basis_points: 0,
+                creators: Some(vec![Creator {
+                    address: ctx.accounts.payer.key(),
+                    verified: false,
+                    share: 100,
+                }]),
+                collection: (collection_mint != Pubkey::default()).then_some(Collection {
+                    verified: false,
+                    key: collection_mint,
+                }),
+                uses: None,
+            };
+
+            let metadata_ctx = CpiContext::new_with_signer(
+                ctx.accounts
+                    .token_metadata_program
+                    .as_ref()
+                    .ok_or(CustomError::MissingMetadataAccounts)?
+                    .to_account_info(),
+                CreateMetadataAccountsV3 {
+                    payer: ctx.accounts.payer.to_account_info(),
+                    update_authority: ctx.accounts.mint.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    metadata: ctx
+                        .accounts
+                        .metadata
+                        .as_ref()
+                        .ok_or(CustomError::MissingMetadataAccounts)?
+                        .to_account_info(),
+                    mint_authority: ctx.accounts.mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                &signer,
+            );
+
+            create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
+
+            // The launching wallet signs the transaction, so it can verify itself as creator
+            let metadata_program = ctx
+                .accounts
+                .token_metadata_program
+                .as_ref()
+                .ok_or(CustomError::MissingMetadataAccounts)?
+                .to_account_info();
+            let metadata_account = ctx
+                .accounts
+                .metadata
+                .as_ref()
+                .ok_or(CustomError::MissingMetadataAccounts)?
+                .to_account_info();
+            sign_metadata(CpiContext::new(
+                metadata_program.clone(),
+                SignMetadata {
+                    creator: ctx.accounts.payer.to_account_info(),
+                    metadata: metadata_account.clone(),
+                },
+            ))?;
+
+            // Every launch joins the platform collection, whose update authority is a program PDA
+            if collection_mint != Pubkey::default() {
+                let accounts = &ctx.accounts;
+                let (
+                    Some(collection_mint_account),
+                    Some(collection_metadata),
+                    Some(collection_master_edition),
+                    Some(collection_authority),
+                ) = (
+                    &accounts.collection_mint,
+                    &accounts.collection_metadata,
+                    &accounts.collection_master_edition,
+                    &accounts.collection_authority,
+                )
+                else {
+                    return err!(CustomError::MissingCollectionAccounts);
+                };
+                require!(
+                    collection_mint_account.key() == collection_mint,
+                    CustomError::MissingCollectionAccounts
+                );
+                let authority_seeds = &[
+                    COLLECTION_AUTHORITY_SEED.as_bytes(),
+                    &[ctx.bumps.collection_authority],
+                ];
+                verify_sized_collection_item(
+                    CpiContext::new_with_signer(
+                        metadata_program,
+                        VerifySizedCollectionItem {
+                            payer: accounts.payer.to_account_info(),
+                            metadata: metadata_account,
+                            collection_authority: collection_authority.to_account_info(),
+                            collection_mint: collection_mint_account.to_account_info(),
+                            collection_metadata: collection_metadata.to_account_info(),
+                            collection_master_edition: collection_master_edition.to_account_info(),
+                        },
+                        &[&authority_seeds[..]],
+                    ),
+                    None,
+                )?;
+            }
+        }
 
-        let metadata_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
+        // The vault PDA is both the token account and its own authority
+        let mint_key = ctx.accounts.mint.key();
+        let vault_seeds = &[mint_key.as_ref(), &[ctx.bumps.token_vault]];
+        let vault_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+            &ExtensionType::get_required_init_account_extensions(&extensions),
+        )?;
+        create_pda_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.token_vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.rent.minimum_balance(vault_len),
+            vault_len,
+            &ctx.accounts.token_program.key(),
+            &[&vault_seeds[..]],
+        )?;
+        token_interface::initialize_account3(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::InitializeAccount3 {
+                account: ctx.accounts.token_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: ctx.accounts.token_vault.to_account_info(),
+            },
+        ))?;
+        // The vesting vault belongs to the vesting PDA, nothing but claim_vested can move its tokens
+        if vesting_amount > 0 {
+            let vesting = ctx
+                .accounts
+                .vesting
+                .as_ref()
+                .ok_or(CustomError::MissingVestingAccount)?;
+            let vesting_vault = ctx
+                .accounts
+                .vesting_vault
+                .as_ref()
+                .ok_or(CustomError::MissingVestingAccount)?;
+            let vesting_vault_seeds = &[
+                VESTING_VAULT_SEED.as_bytes(),
+                mint_key.as_ref(),
+                &[ctx.bumps.vesting_vault],
+            ];
+            create_pda_account(
+                &ctx.accounts.payer.to_account_info(),
+                &vesting_vault.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.minimum_balance(vault_len),
+                vault_len,
+                &ctx.accounts.token_program.key(),
+                &[&vesting_vault_seeds[..]],
+            )?;
+            token_interface::initialize_account3(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::InitializeAccount3 {
+                    account: vesting_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: vesting.to_account_info(),
+                },
+            ))?;
+        }
+        if airdrop_amount > 0 {
+            let airdrop = ctx
+                .accounts
+                .airdrop
+                .as_ref()
+                .ok_or(CustomError::MissingAirdropAccount)?;
+            let airdrop_vault = ctx
+                .accounts
+                .airdrop_vault
+                .as_ref()
+                .ok_or(CustomError::MissingAirdropAccount)?;
+            let airdrop_vault_seeds = &[
+                AIRDROP_VAULT_SEED.as_bytes(),
+                mint_key.as_ref(),
+                &[ctx.bumps.airdrop_vault],
+            ];
+            create_pda_account(
+                &ctx.accounts.payer.to_account_info(),
+                &airdrop_vault.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.minimum_balance(vault_len),
+                vault_len,
+                &ctx.accounts.token_program.key(),
+                &[&airdrop_vault_seeds[..]],
+            )?;
+            token_interface::initialize_account3(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::InitializeAccount3 {
+                    account: airdrop_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: airdrop.to_account_info(),
+                },
+            ))?;
+        }
+        associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
                 payer: ctx.accounts.payer.to_account_info(),
-                update_authority: ctx.accounts.mint.to_account_info(),
+                associated_token: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
-                metadata: ctx.accounts.metadata.to_account_info(),
-                mint_authority: ctx.accounts.mint.to_account_info(),
                 system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
             },
-            &signer,
-        );
+        ))?;
 
-        create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
-
-        // Transfer SOL from buyer to contract account
-        let transfer_instruction = transfer(
-            &ctx.accounts.payer.key(),
-            &ctx.accounts.escrow_account.key(),
+        // Transfer quote from buyer to contract account
+        let quote = ctx.accounts.token_pools.quote_accounts(
+            ctx.accounts.payer_quote_account.as_deref(),
+            ctx.accounts.quote_vault.as_deref(),
+            ctx.accounts.quote_token_program.as_ref(),
+        )?;
+        deposit_quote(
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.escrow_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            quote,
             amount,
-        );
-        invoke(
-            &transfer_instruction,
-            &[
-                ctx.accounts.payer.to_account_info(),
-                ctx.accounts.escrow_account.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
         )?;
 
-        let buy_fee: u64 = calculate_fee(amount, ctx.accounts.global_info.fee_percent);
+        let buy_fee: u64 = calculate_fee(amount, ctx.accounts.token_pools.fee_percent);
         let effective_sol: u64 = amount - buy_fee;
-        let token_price: u64 = get_price(
-            ctx.accounts.global_info.initial_amount,
-            ctx.accounts.global_info.total_supply,
-        );
-        let token_amount: u64 = (effective_sol * 1e9 as u64) / token_price;
+        let token_amount: u64 = mul_div(
+            effective_sol,
+            ctx.accounts.token_pools.token_unit(),
+            floor_price,
+        )?;
 
         msg!("Token mint created successfully.");
 
@@ -126,9 +1038,49 @@ mod token_minter {
                 },
                 &signer,
             ),
-            ctx.accounts.global_info.total_supply - token_amount,
+            total_supply - token_amount - vesting_amount - airdrop_amount,
         )?;
 
+        if let Some(vesting_vault) = ctx
+            .accounts
+            .vesting_vault
+            .as_ref()
+            .filter(|_| vesting_amount > 0)
+        {
+            mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        authority: ctx.accounts.mint.to_account_info(),
+                        to: vesting_vault.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                    },
+                    &signer,
+                ),
+                vesting_amount,
+            )?;
+        }
+
+        if let Some(airdrop_vault) = ctx
+            .accounts
+            .airdrop_vault
+            .as_ref()
+            .filter(|_| airdrop_amount > 0)
+        {
+            mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        authority: ctx.accounts.mint.to_account_info(),
+                        to: airdrop_vault.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                    },
+                    &signer,
+                ),
+                airdrop_amount,
+            )?;
+        }
+
         mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
@@ -142,36 +1094,147 @@ mod token_minter {
             token_amount,
         )?;
 
-        ctx.accounts.token_pools.token_reserve =
-            ctx.accounts.global_info.total_supply - token_amount;
+        // Supply is fixed at total_supply from here on, the mint was created without a freeze authority
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::SetAuthority {
+                    current_authority: ctx.accounts.mint.to_account_info(),
+                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                },
+                &signer,
+            ),
+            spl_token_2022::instruction::AuthorityType::MintTokens,
+            None,
+        )?;
+
+        ctx.accounts.token_pools.token_reserve = curve_supply - token_amount;
         ctx.accounts.token_pools.sol_reserve += effective_sol;
+        // The creator's initial buy is minted straight to them, no transfer fee applies
+        match &mut ctx.accounts.position {
+            Some(position) => {
+                position.bind(ctx.accounts.payer.key(), ctx.accounts.mint.key());
+                position.record_buy(amount, token_amount);
+                ctx.accounts
+                    .token_pools
+                    .record_holder(0, position.token_balance);
+            }
+            None => require!(
+                !ctx.accounts.token_pools.holder_gated(),
+                CustomError::MissingPosition
+            ),
+        }
+        ctx.accounts.token_pools.fees_collected += buy_fee;
         ctx.accounts.token_pools.launched = 0;
         ctx.accounts.global_info.token_count += 1;
+        // The first observation window starts at creation
+        let now = Clock::get()?.unix_timestamp;
+        let token_pools = &mut ctx.accounts.token_pools;
+        token_pools.accumulate_price(now);
+        token_pools.last_price = token_pools.spot_price()?;
+        token_pools.twap_checkpoint_at = now;
 
         Ok(())
     }
 
-    pub fn buy_token(ctx: Context<BuyToken>, amount: u64) -> Result<()> {
-        let buy_fee: u64 = calculate_fee(amount, ctx.accounts.global_info.fee_percent);
+    pub fn buy_token(
+        ctx: Context<BuyToken>,
+        amount: u64,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        ctx.accounts.token_pools.open_trading(&clock)?;
+        ctx.accounts
+            .token_pools
+            .accumulate_price(clock.unix_timestamp);
+        ctx.accounts.token_pools.end_dutch_auction(clock.slot)?;
+        let buy_fee: u64 = calculate_fee(amount, ctx.accounts.token_pools.fee_percent);
         let effective_sol: u64 = amount - buy_fee;
-        let token_price: u64 = get_price(
-            ctx.accounts.token_pools.sol_reserve,
-            ctx.accounts.token_pools.token_reserve,
-        );
-        let token_amount: u64 = (effective_sol * 1e9 as u64) / token_price;
-        // Transfer SOL from buyer to contract account
-        let transfer_instruction = transfer(
-            &ctx.accounts.buyer.key(),
-            &ctx.accounts.escrow_account.key(),
+        let dutch = ctx.accounts.token_pools.dutch_active(clock.slot);
+        let token_price: u64 = if dutch {
+            ctx.accounts.token_pools.dutch_price(clock.slot)
+        } else {
+            get_price(
+                ctx.accounts.token_pools.sol_reserve,
+                ctx.accounts.token_pools.token_reserve,
+                ctx.accounts.token_pools.decimals,
+            )?
+        };
+        let token_amount: u64 = mul_div(
+            effective_sol,
+            ctx.accounts.token_pools.token_unit(),
+            token_price,
+        )?;
+        // The buyer only receives what is left after the transfer fee withheld on the vault transfer
+        let received_amount: u64 = token_amount
+            - withheld_transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount)?;
+
+        if dutch {
+            ctx.accounts.token_pools.auction_sold += token_amount;
+            require!(
+                ctx.accounts.token_pools.auction_sold <= ctx.accounts.token_pools.auction_supply,
+                CustomError::InvalidTokenAmount
+            );
+        }
+
+        let buyer_info = &mut ctx.accounts.buyer_info;
+        buyer_info.sol_amount += amount;
+        buyer_info.token_amount += received_amount;
+
+        // During the allowlist phase only wallets in the merkle tree can buy, up to their allocation
+        if ctx.accounts.token_pools.in_allowlist_phase(&clock) {
+            let proof = allowlist_proof.ok_or(CustomError::NotAllowlisted)?;
+            require!(
+                verify_allowlist_proof(
+                    ctx.accounts.token_pools.allowlist_root,
+                    &ctx.accounts.buyer.key(),
+                    &proof
+                ),
+                CustomError::NotAllowlisted
+            );
+            require!(
+                buyer_info.sol_amount <= proof.allocation,
+                CustomError::BuyLimitExceeded
+            );
+        }
+
+        // Anti-sniper caps only apply during the first seconds after public trading opens,
+        // whether or not anyone traded at the open
+        let global_info = &ctx.accounts.global_info;
+        if clock.unix_timestamp
+            < ctx.accounts.token_pools.public_trading_starts_at()
+                + global_info.sniper_window_seconds
+        {
+            let max_token_amount = mul_div(
+                ctx.accounts.token_pools.total_supply,
+                global_info.max_buy_percent as u64,
+                10000,
+            )?;
+            let max_buy_amount = ctx.accounts.token_pools.max_buy_amount(global_info);
+            require!(
+                max_buy_amount == 0 || buyer_info.sol_amount <= max_buy_amount,
+                CustomError::BuyLimitExceeded
+            );
+            // token_amount is what the wallet received after any transfer fee, so the cap
+            // limits what it actually holds rather than what left the vault
+            require!(
+                global_info.max_buy_percent == 0 || buyer_info.token_amount <= max_token_amount,
+                CustomError::BuyLimitExceeded
+            );
+        }
+
+        // Transfer quote from buyer to contract account
+        let quote = ctx.accounts.token_pools.quote_accounts(
+            ctx.accounts.buyer_quote_account.as_deref(),
+            ctx.accounts.quote_vault.as_deref(),
+            ctx.accounts.quote_token_program.as_ref(),
+        )?;
+        deposit_quote(
+            ctx.accounts.buyer.to_account_info(),
+            ctx.accounts.escrow_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            quote,
             amount,
-        );
-        invoke(
-            &transfer_instruction,
-            &[
-                ctx.accounts.buyer.to_account_info(),
-                ctx.accounts.escrow_account.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
         )?;
 
         require!(
@@ -185,145 +1248,898 @@ mod token_minter {
 
         let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            token::Transfer {
+            token_interface::TransferChecked {
                 from: ctx.accounts.token_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.buyer_token_account.to_account_info(),
                 authority: ctx.accounts.token_vault.to_account_info(),
             },
             signer_seeds,
         );
-        token::transfer(transfer_ctx, token_amount)?;
+        token_interface::transfer_checked(transfer_ctx, token_amount, ctx.accounts.mint.decimals)?;
 
-        ctx.accounts.token_pools.sol_reserve += amount;
-        ctx.accounts.token_pools.token_reserve -= token_amount;
+        // The fee stays in the escrow but is kept out of the curve, same as the initial buy.
+        // Dutch proceeds are held apart until the auction ends, see end_dutch_auction.
+        ctx.accounts.token_pools.fees_collected += buy_fee;
+        if dutch {
+            ctx.accounts.token_pools.auction_raised += effective_sol;
+            ctx.accounts.token_pools.end_dutch_auction(clock.slot)?;
+        } else {
+            ctx.accounts.token_pools.sol_reserve += effective_sol;
+            ctx.accounts.token_pools.token_reserve -= token_amount;
+        }
+        ctx.accounts
+            .token_pools
+            .refresh_price(clock.slot, token_price)?;
+
+        // Holder gated pools need every trade to go through a position to keep the stats honest
+        match &mut ctx.accounts.position {
+            Some(position) => {
+                position.bind(ctx.accounts.buyer.key(), ctx.accounts.mint.key());
+                let balance_before = position.token_balance;
+                position.record_buy(amount, received_amount);
+                ctx.accounts
+                    .token_pools
+                    .record_holder(balance_before, position.token_balance);
+            }
+            None => require!(
+                !ctx.accounts.token_pools.holder_gated(),
+                CustomError::MissingPosition
+            ),
+        }
+        // Candles close on the price the trade left behind, like the TWAP
+        let last_price = ctx.accounts.token_pools.last_price;
+        ctx.accounts
+            .candles
+            .load_mut()?
+            .record(clock.unix_timestamp, last_price, effective_sol);
 
         Ok(())
     }
 
     pub fn sell_token(ctx: Context<SellToken>, token_amount: u64) -> Result<()> {
-        let sell_fee: u64 = calculate_fee(token_amount, ctx.accounts.global_info.fee_percent);
-        let effective_token_amount: u64 = token_amount - sell_fee;
+        let clock = Clock::get()?;
+        ctx.accounts.token_pools.open_trading(&clock)?;
+        ctx.accounts
+            .token_pools
+            .accumulate_price(clock.unix_timestamp);
+        // Dutch auctions only sell, holders exit through the curve once the auction ends
+        ctx.accounts.token_pools.end_dutch_auction(clock.slot)?;
+        require!(
+            !ctx.accounts.token_pools.dutch_active(clock.slot),
+            CustomError::AuctionNotEnded
+        );
+        // Only the tokens that reach the vault after the withheld transfer fee are priced
+        let received_amount: u64 = token_amount
+            - withheld_transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount)?;
+        let sell_fee: u64 = calculate_fee(received_amount, ctx.accounts.token_pools.fee_percent);
+        let effective_token_amount: u64 = received_amount - sell_fee;
         let token_price: u64 = get_price(
             ctx.accounts.token_pools.sol_reserve,
             ctx.accounts.token_pools.token_reserve,
-        );
-        let sol_amount: u64 = (effective_token_amount * token_price) / 1e9 as u64;
+            ctx.accounts.token_pools.decimals,
+        )?;
+        let sol_amount: u64 = mul_div(
+            effective_token_amount,
+            token_price,
+            ctx.accounts.token_pools.token_unit(),
+        )?;
         // Transfer tokens from seller to contract account
 
         let cpi_context = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            token::Transfer {
+            token_interface::TransferChecked {
                 from: ctx.accounts.seller_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.token_vault.to_account_info(),
                 authority: ctx.accounts.seller.to_account_info(),
             },
         );
-        token::transfer(cpi_context, token_amount)?;
+        token_interface::transfer_checked(cpi_context, token_amount, ctx.accounts.mint.decimals)?;
 
+        // The virtual part of the reserve was never deposited and cannot be paid out
         require!(
-            ctx.accounts.token_pools.sol_reserve > sol_amount,
+            ctx.accounts.token_pools.real_sol_reserve() > sol_amount,
             CustomError::InvalidSolAmount
         );
-        // Transfer SOL from contract account to seller
-        **ctx
-            .accounts
-            .escrow_account
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= sol_amount;
-        **ctx
-            .accounts
-            .seller
-            .to_account_info()
-            .try_borrow_mut_lamports()? += sol_amount;
+        // Transfer quote from contract account to seller
+        let quote = ctx.accounts.token_pools.quote_accounts(
+            ctx.accounts.seller_quote_account.as_deref(),
+            ctx.accounts.quote_vault.as_deref(),
+            ctx.accounts.quote_token_program.as_ref(),
+        )?;
+        let binding = ctx.accounts.mint.key();
+        let vault_seeds = &[
+            QUOTE_VAULT_SEED.as_bytes(),
+            binding.as_ref(),
+            &[ctx.bumps.quote_vault],
+        ];
+        withdraw_quote(
+            &ctx.accounts.escrow_account,
+            ctx.accounts.seller.to_account_info(),
+            quote,
+            vault_seeds,
+            sol_amount,
+        )?;
 
         ctx.accounts.token_pools.sol_reserve -= sol_amount;
-        ctx.accounts.token_pools.token_reserve += token_amount;
+        ctx.accounts.token_pools.token_reserve += received_amount;
+        ctx.accounts.token_pools.last_price = ctx.accounts.token_pools.spot_price()?;
+
+        match &mut ctx.accounts.position {
+            Some(position) => {
+                position.bind(ctx.accounts.seller.key(), ctx.accounts.mint.key());
+                let balance_before = position.token_balance;
+                position.record_sell(sol_amount, token_amount)?;
+                ctx.accounts
+                    .token_pools
+                    .record_holder(balance_before, position.token_balance);
+            }
+            None => require!(
+                !ctx.accounts.token_pools.holder_gated(),
+                CustomError::MissingPosition
+            ),
+        }
+        let last_price = ctx.accounts.token_pools.last_price;
+        ctx.accounts
+            .candles
+            .load_mut()?
+            .record(clock.unix_timestamp, last_price, sol_amount);
 
         Ok(())
     }
 
-    pub fn add_liquidity(ctx: Context<AddLiquidity>, sol_price: u64) -> Result<()> {
-        let init_coin_amount =
-            ctx.accounts.global_info.target_lp_amount * 1e9 as u64 * 1000 / sol_price;
-        let init_pc_amount = ctx.accounts.token_pools.token_reserve
-            / ctx.accounts.token_pools.sol_reserve
-            * init_coin_amount;
-
-        let pda_account = ctx.accounts.escrow_account.to_account_info();
-        let send_to_account = ctx.accounts.user_token_pc.to_account_info();
-
-        let binding = ctx.accounts.token_vault.mint.key();
-        let seeds = &[binding.as_ref(), &[ctx.bumps.token_vault]];
-        let signer_seeds = &[&seeds[..]];
-
-        let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            token::Transfer {
-                from: ctx.accounts.token_vault.to_account_info(),
-                to: ctx.accounts.user_token_coin.to_account_info(),
-                authority: ctx.accounts.token_vault.to_account_info(),
-            },
-            signer_seeds,
+    pub fn commit_auction(ctx: Context<CommitAuction>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.token_pools.launch_mode == LAUNCH_MODE_BATCH_AUCTION,
+            CustomError::InvalidLaunchMode
+        );
+        require!(
+            !ctx.accounts.token_pools.cancelled,
+            CustomError::LaunchCancelled
+        );
+        require!(!ctx.accounts.token_pools.paused, CustomError::PoolPaused);
+        require!(
+            now >= ctx.accounts.token_pools.trading_starts_at,
+            CustomError::TradingNotStarted
+        );
+        require!(
+            now < ctx.accounts.token_pools.auction_ends_at,
+            CustomError::AuctionEnded
         );
 
-        token::transfer(transfer_ctx, init_pc_amount)?;
-
-        **pda_account.try_borrow_mut_lamports()? -= init_coin_amount;
-        **send_to_account.try_borrow_mut_lamports()? += init_coin_amount;
-
-        ctx.accounts.token_pools.launched = 1;
+        // Transfer quote from buyer to contract account
+        let quote = ctx.accounts.token_pools.quote_accounts(
+            ctx.accounts.buyer_quote_account.as_deref(),
+            ctx.accounts.quote_vault.as_deref(),
+            ctx.accounts.quote_token_program.as_ref(),
+        )?;
+        deposit_quote(
+            ctx.accounts.buyer.to_account_info(),
+            ctx.accounts.escrow_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            quote,
+            amount,
+        )?;
 
-        // token::sync_native(CpiContext::new(
-        //     ctx.accounts.token_program.to_account_info(),
-        //     token::SyncNative {
-        //         account: ctx.accounts.user_token_pc.to_account_info(),
-        //     },
-        // ))?;
+        ctx.accounts.commitment.sol_amount += amount;
+        ctx.accounts.token_pools.auction_committed += amount;
 
         Ok(())
     }
 
-    pub fn withdraw_balance(ctx: Context<WithdrawBalance>, amount: u64) -> Result<()> {
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        let token_pools = &mut ctx.accounts.token_pools;
         require!(
-            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
-            CustomError::NotOwner
+            token_pools.launch_mode == LAUNCH_MODE_BATCH_AUCTION,
+            CustomError::InvalidLaunchMode
         );
-        let pool_amount = **ctx.accounts.escrow_account.lamports.borrow();
-        require!(pool_amount >= amount, CustomError::InvalidSolAmount);
-        **ctx.accounts.escrow_account.try_borrow_mut_lamports()? -= amount;
-        **ctx.accounts.admin.try_borrow_mut_lamports()? += amount;
-
-        ctx.accounts.token_pools.sol_reserve -= amount;
-        Ok(())
-    }
-
-    pub fn set_fee_percent(ctx: Context<CommonCtx>, fee_percent: u32) -> Result<()> {
         require!(
-            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
-            CustomError::NotOwner
+            Clock::get()?.unix_timestamp >= token_pools.auction_ends_at,
+            CustomError::AuctionNotEnded
         );
-        ctx.accounts.global_info.fee_percent = fee_percent;
-        Ok(())
-    }
-
-    pub fn set_target_market_cap(ctx: Context<CommonCtx>, target_market_cap: u64) -> Result<()> {
         require!(
-            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
-            CustomError::NotOwner
+            !token_pools.auction_settled,
+            CustomError::AuctionAlreadySettled
         );
-        ctx.accounts.global_info.target_market_cap = target_market_cap;
+        token_pools.accumulate_price(Clock::get()?.unix_timestamp);
+
+        // Single clearing price: demand spread over the offered supply, bounded by
+        // the curve's starting price below and the optional max price above
+        let committed = token_pools.auction_committed;
+        let unit = token_pools.token_unit();
+        let floor_price = get_price(
+            token_pools.sol_reserve,
+            token_pools.token_reserve,
+            token_pools.decimals,
+        )?;
+        let mut price = floor_price.max(mul_div(committed, unit, token_pools.auction_supply)?);
+        if token_pools.auction_max_price > 0 {
+            price = price.min(token_pools.auction_max_price);
+        }
+        let sold = token_pools
+            .auction_supply
+            .min(mul_div(committed, unit, price)?);
+        let raised = if sold == token_pools.auction_supply {
+            committed.min(mul_div(sold, price, unit)?)
+        } else {
+            committed
+        };
+
+        let fee = calculate_fee(raised, token_pools.fee_percent);
+        token_pools.auction_price = price;
+        token_pools.auction_sold = sold;
+        token_pools.auction_raised = raised;
+        token_pools.auction_settled = true;
+        token_pools.sol_reserve += raised - fee;
+        token_pools.fees_collected += fee;
+        token_pools.token_reserve -= sold;
+        token_pools.last_price = token_pools.spot_price()?;
+
         Ok(())
     }
 
-    pub fn set_target_lp_amount(ctx: Context<CommonCtx>, target_lp_amount: u64) -> Result<()> {
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
         require!(
-            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            !ctx.accounts.token_pools.cancelled,
+            CustomError::LaunchCancelled
+        );
+        let vesting = &mut ctx.accounts.vesting;
+        let amount = vesting.vested_amount(Clock::get()?.unix_timestamp)? - vesting.claimed_amount;
+        require!(amount > 0, CustomError::NothingToClaim);
+        vesting.claimed_amount += amount;
+
+        let binding = ctx.accounts.mint.key();
+        let seeds = &[
+            VESTING_SEED.as_bytes(),
+            binding.as_ref(),
+            &[ctx.bumps.vesting],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.vesting_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                authority: ctx.accounts.vesting.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        let received_amount =
+            amount - withheld_transfer_fee(&ctx.accounts.mint.to_account_info(), amount)?;
+        ctx.accounts.token_pools.credit_position(
+            ctx.accounts.position.as_deref_mut().map(|p| &mut **p),
+            ctx.accounts.beneficiary.key(),
+            ctx.accounts.mint.key(),
+            0,
+            received_amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.token_pools.cancelled,
+            CustomError::LaunchCancelled
+        );
+        let airdrop = &mut ctx.accounts.airdrop;
+        require!(
+            Clock::get()?.unix_timestamp < airdrop.expires_at,
+            CustomError::AirdropExpired
+        );
+        // Leaves are keccak(index || wallet || amount)
+        let leaf = hashv(&[
+            &index.to_le_bytes(),
+            ctx.accounts.claimer.key().as_ref(),
+            &amount.to_le_bytes(),
+        ])
+        .0;
+        require!(
+            verify_merkle_proof(airdrop.merkle_root, leaf, &proof),
+            CustomError::InvalidAirdropProof
+        );
+        let byte = airdrop
+            .claimed_bitmap
+            .get_mut(index as usize / 8)
+            .ok_or(CustomError::InvalidAirdropProof)?;
+        let bit = 1u8 << (index % 8);
+        require!(*byte & bit == 0, CustomError::AlreadyClaimed);
+        *byte |= bit;
+        airdrop.claimed_amount += amount;
+        require!(
+            airdrop.claimed_amount <= airdrop.total_amount,
+            CustomError::InvalidTokenAmount
+        );
+
+        let binding = ctx.accounts.mint.key();
+        let seeds = &[
+            AIRDROP_SEED.as_bytes(),
+            binding.as_ref(),
+            &[ctx.bumps.airdrop],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.airdrop_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.claimer_token_account.to_account_info(),
+                authority: ctx.accounts.airdrop.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        let received_amount =
+            amount - withheld_transfer_fee(&ctx.accounts.mint.to_account_info(), amount)?;
+        ctx.accounts.token_pools.credit_position(
+            ctx.accounts.position.as_deref_mut().map(|p| &mut **p),
+            ctx.accounts.claimer.key(),
+            ctx.accounts.mint.key(),
+            0,
+            received_amount,
+        )?;
+
+        Ok(())
+    }
+
+    // Permissionless once expired, burns whatever was not claimed
+    pub fn close_airdrop(ctx: Context<CloseAirdrop>) -> Result<()> {
+        let airdrop = &mut ctx.accounts.airdrop;
+        require!(
+            Clock::get()?.unix_timestamp >= airdrop.expires_at,
+            CustomError::AirdropNotExpired
+        );
+        let unclaimed = airdrop.total_amount - airdrop.claimed_amount;
+        require!(unclaimed > 0, CustomError::NothingToClaim);
+        airdrop.claimed_amount = airdrop.total_amount;
+
+        let binding = ctx.accounts.mint.key();
+        let seeds = &[
+            AIRDROP_SEED.as_bytes(),
+            binding.as_ref(),
+            &[ctx.bumps.airdrop],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.airdrop_vault.to_account_info(),
+                    authority: ctx.accounts.airdrop.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            unclaimed,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn claim_auction(ctx: Context<ClaimAuction>) -> Result<()> {
+        let token_pools = &ctx.accounts.token_pools;
+        require!(token_pools.auction_settled, CustomError::AuctionNotSettled);
+        require!(
+            !ctx.accounts.commitment.claimed,
+            CustomError::AlreadyClaimed
+        );
+        let committed = ctx.accounts.commitment.sol_amount;
+        require!(committed > 0, CustomError::NothingToClaim);
+
+        // Every participant gets the same share of the sold supply and of the raised SOL
+        let token_amount = mul_div(
+            committed,
+            token_pools.auction_sold,
+            token_pools.auction_committed,
+        )?;
+        let spent = mul_div_ceil(
+            committed,
+            token_pools.auction_raised,
+            token_pools.auction_committed,
+        )?;
+        let refund = committed - spent;
+        ctx.accounts.commitment.claimed = true;
+        ctx.accounts.token_pools.auction_refunded += refund;
+        ctx.accounts.token_pools.auction_claimed += token_amount;
+
+        let binding = ctx.accounts.mint.key();
+        let seeds = &[binding.as_ref(), &[ctx.bumps.token_vault]];
+        let signer_seeds = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.token_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.claimer_token_account.to_account_info(),
+                authority: ctx.accounts.token_vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token_interface::transfer_checked(transfer_ctx, token_amount, ctx.accounts.mint.decimals)?;
+
+        let received_amount = token_amount
+            - withheld_transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount)?;
+        ctx.accounts.token_pools.credit_position(
+            ctx.accounts.position.as_deref_mut().map(|p| &mut **p),
+            ctx.accounts.claimer.key(),
+            ctx.accounts.mint.key(),
+            spent,
+            received_amount,
+        )?;
+
+        // Refund the quote that did not clear
+        let quote = ctx.accounts.token_pools.quote_accounts(
+            ctx.accounts.claimer_quote_account.as_deref(),
+            ctx.accounts.quote_vault.as_deref(),
+            ctx.accounts.quote_token_program.as_ref(),
+        )?;
+        let vault_seeds = &[
+            QUOTE_VAULT_SEED.as_bytes(),
+            binding.as_ref(),
+            &[ctx.bumps.quote_vault],
+        ];
+        withdraw_quote(
+            &ctx.accounts.escrow_account,
+            ctx.accounts.claimer.to_account_info(),
+            quote,
+            vault_seeds,
+            refund,
+        )?;
+
+        Ok(())
+    }
+
+    // sol_price is the USD price * 1000 of the quote asset, USD-pegged quote mints ignore it.
+    // Pools with a holder cap take the largest positions as remaining accounts.
+    pub fn add_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
+        sol_price: u64,
+    ) -> Result<()> {
+        // Optionally graduate on the time-averaged market cap rather than the last spot price
+        let token_pools = &mut ctx.accounts.token_pools;
+        // Graduation runs once, which also keeps graduated_count honest
+        require!(token_pools.launched == 0, CustomError::LaunchGraduated);
+        let quote_price = token_pools.quote_usd_price(sol_price);
+        let clock = Clock::get()?;
+        token_pools.accumulate_price(clock.unix_timestamp);
+        token_pools.end_dutch_auction(clock.slot)?;
+        require!(
+            !token_pools.dutch_active(clock.slot),
+            CustomError::AuctionNotEnded
+        );
+        if token_pools.graduation_twap_window > 0 {
+            let twap = token_pools.graduation_twap()?;
+            // Same USD * 1000 quote price convention as init_coin_amount below
+            let market_cap = twap as u128
+                * (token_pools.total_supply / token_pools.token_unit()) as u128
+                * quote_price as u128
+                / 1000
+                / 10u128.pow(token_pools.quote_decimals as u32);
+            require!(
+                market_cap >= token_pools.target_market_cap as u128,
+                CustomError::MarketCapNotReached
+            );
+        }
+        require!(!token_pools.paused, CustomError::PoolPaused);
+        // Optionally require a spread of holders, which stops single-wallet graduations
+        require!(
+            token_pools.holder_count >= token_pools.min_holder_count,
+            CustomError::NotEnoughHolders
+        );
+        if token_pools.max_holder_percent > 0 {
+            // Any position that was not passed in holds at most what the passed ones leave of
+            // holder_supply, so the largest holder is bounded without trusting the caller.
+            // Positions only exist at their (mint, owner) PDA, the account key identifies them.
+            let mint_key = ctx.accounts.mint.key();
+            let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+            let mut passed_supply: u64 = 0;
+            let mut largest: u64 = 0;
+            for info in ctx.remaining_accounts.iter() {
+                let position = Account::<Position>::try_from(info)?;
+                require!(position.mint == mint_key, CustomError::InvalidPosition);
+                require!(!seen.contains(info.key), CustomError::DuplicatePosition);
+                seen.push(info.key());
+                passed_supply += position.token_balance;
+                largest = largest.max(position.token_balance);
+            }
+            let unseen_supply = token_pools
+                .holder_supply
+                .checked_sub(passed_supply)
+                .ok_or(CustomError::InvalidPosition)?;
+            require!(
+                largest.max(unseen_supply)
+                    <= mul_div(
+                        token_pools.holder_supply,
+                        token_pools.max_holder_percent as u64,
+                        10000
+                    )?,
+                CustomError::HolderConcentrationTooHigh
+            );
+        }
+
+        let init_coin_amount = ctx.accounts.token_pools.target_lp_amount
+            * 10u64.pow(ctx.accounts.token_pools.quote_decimals as u32)
+            * 1000
+            / quote_price;
+        let init_pc_amount = ctx.accounts.token_pools.token_reserve
+            / ctx.accounts.token_pools.sol_reserve
+            * init_coin_amount;
+
+        let binding = ctx.accounts.token_vault.mint.key();
+        let seeds = &[binding.as_ref(), &[ctx.bumps.token_vault]];
+        let signer_seeds = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.token_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.user_token_coin.to_account_info(),
+                authority: ctx.accounts.token_vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        token_interface::transfer_checked(
+            transfer_ctx,
+            init_pc_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        // For quote-token launches user_token_pc is a token account of the quote mint
+        let quote = ctx.accounts.token_pools.quote_accounts(
+            Some(&ctx.accounts.user_token_pc),
+            ctx.accounts.quote_vault.as_deref(),
+            ctx.accounts.quote_token_program.as_ref(),
+        )?;
+        let vault_seeds = &[
+            QUOTE_VAULT_SEED.as_bytes(),
+            binding.as_ref(),
+            &[ctx.bumps.quote_vault],
+        ];
+        withdraw_quote(
+            &ctx.accounts.escrow_account,
+            ctx.accounts.user_token_pc.to_account_info(),
+            quote,
+            vault_seeds,
+            init_coin_amount,
+        )?;
+
+        ctx.accounts.token_pools.launched = 1;
+        ctx.accounts.launch.status = LAUNCH_STATUS_GRADUATED;
+        ctx.accounts.creator_info.graduated_count += 1;
+
+        // token::sync_native(CpiContext::new(
+        //     ctx.accounts.token_program.to_account_info(),
+        //     token::SyncNative {
+        //         account: ctx.accounts.user_token_pc.to_account_info(),
+        //     },
+        // ))?;
+
+        Ok(())
+    }
+
+    pub fn set_allowlist(
+        ctx: Context<SetAllowlist>,
+        allowlist_root: [u8; 32],
+        allowlist_ends_at: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.token_pools.creator == ctx.accounts.creator.key(),
+            CustomError::NotCreator
+        );
+        require!(
+            Clock::get()?.unix_timestamp < ctx.accounts.token_pools.trading_starts_at,
+            CustomError::TradingAlreadyStarted
+        );
+        require!(
+            allowlist_ends_at >= ctx.accounts.token_pools.trading_starts_at,
+            CustomError::InvalidAllowlistEnd
+        );
+        ctx.accounts.token_pools.allowlist_root = allowlist_root;
+        ctx.accounts.token_pools.allowlist_ends_at = allowlist_ends_at;
+        Ok(())
+    }
+
+    pub fn cancel_launch(ctx: Context<CancelLaunch>) -> Result<()> {
+        let token_pools = &mut ctx.accounts.token_pools;
+        require!(
+            token_pools.creator == ctx.accounts.creator.key(),
+            CustomError::NotCreator
+        );
+        require!(
+            Clock::get()?.unix_timestamp < token_pools.trading_starts_at,
+            CustomError::TradingAlreadyStarted
+        );
+        require!(!token_pools.cancelled, CustomError::LaunchCancelled);
+        // Nothing can leave a cancelled pool, so launches holding an initial buy cannot cancel
+        require!(
+            token_pools.real_sol_reserve() == 0 && token_pools.fees_collected == 0,
+            CustomError::CancelAfterInitialBuy
+        );
+        token_pools.cancelled = true;
+        ctx.accounts.launch.status = LAUNCH_STATUS_CANCELLED;
+        ctx.accounts.creator_info.cancelled_count += 1;
+        Ok(())
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.token_pools.creator == ctx.accounts.creator.key(),
+            CustomError::NotCreator
+        );
+        let launch_index = ctx.accounts.token_pools.launch_index.to_le_bytes();
+        let seeds = &[MINT_SEED.as_bytes(), &launch_index, &[ctx.bumps.mint]];
+        let Some(metadata) = &ctx.accounts.metadata else {
+            // Token-2022 launches keep their metadata on the mint
+            return ctx.accounts.update_token_2022_fields(
+                vec![
+                    (Field::Name, name),
+                    (Field::Symbol, symbol),
+                    (Field::Uri, uri),
+                ],
+                &[&seeds[..]],
+            );
+        };
+        // Creators and collection are carried over, Metaplex keeps their verification
+        let token_data: DataV2 = DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: metadata.creators.clone(),
+            collection: metadata.collection.clone(),
+            uses: None,
+        };
+        update_metadata_accounts_v2(
+            ctx.accounts.update_ctx(&[&seeds[..]])?,
+            None,
+            Some(token_data),
+            None,
+            None,
+        )
+    }
+
+    // Irreversible, Metaplex rejects every later update once is_mutable is false and
+    // Token-2022 once the metadata has no update authority
+    pub fn lock_metadata(ctx: Context<UpdateMetadata>) -> Result<()> {
+        require!(
+            ctx.accounts.token_pools.creator == ctx.accounts.creator.key(),
+            CustomError::NotCreator
+        );
+        let launch_index = ctx.accounts.token_pools.launch_index.to_le_bytes();
+        let seeds = &[MINT_SEED.as_bytes(), &launch_index, &[ctx.bumps.mint]];
+        if ctx.accounts.metadata.is_none() {
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(CustomError::MissingMetadataAccounts)?;
+            invoke_signed(
+                &spl_token_metadata_interface::instruction::update_authority(
+                    &token_program.key(),
+                    &ctx.accounts.mint.key(),
+                    &ctx.accounts.mint.key(),
+                    Default::default(),
+                ),
+                &[ctx.accounts.mint.to_account_info()],
+                &[&seeds[..]],
+            )?;
+            return Ok(());
+        }
+        update_metadata_accounts_v2(
+            ctx.accounts.update_ctx(&[&seeds[..]])?,
+            None,
+            None,
+            None,
+            Some(false),
+        )
+    }
+
+    pub fn set_launch_profile(
+        ctx: Context<SetLaunchProfile>,
+        profile: LaunchProfileParams,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.token_pools.creator == ctx.accounts.creator.key(),
+            CustomError::NotCreator
+        );
+        // Frozen once the pool graduates
+        require!(
+            ctx.accounts.token_pools.launched == 0,
+            CustomError::LaunchGraduated
+        );
+        require!(
+            profile.description.len() <= MAX_DESCRIPTION_LEN
+                && profile.website.len() <= MAX_LINK_LEN
+                && profile.twitter.len() <= MAX_LINK_LEN
+                && profile.telegram.len() <= MAX_LINK_LEN,
+            CustomError::ProfileFieldTooLong
+        );
+        let launch_profile = &mut ctx.accounts.launch_profile;
+        launch_profile.mint = ctx.accounts.mint.key();
+        launch_profile.description = profile.description;
+        launch_profile.website = profile.website;
+        launch_profile.twitter = profile.twitter;
+        launch_profile.telegram = profile.telegram;
+        launch_profile.image_hash = profile.image_hash;
+        launch_profile.updated_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    // Permissionless, moves the graduation TWAP's observation window forward
+    pub fn checkpoint_twap(ctx: Context<CheckpointTwap>) -> Result<()> {
+        let token_pools = &mut ctx.accounts.token_pools;
+        let now = Clock::get()?.unix_timestamp;
+        token_pools.accumulate_price(now);
+        token_pools.checkpoint_twap(now)
+    }
+
+    // Permissionless, checks that the escrow and the token vault still cover the pool's accounting
+    pub fn audit_pool(ctx: Context<AuditPool>) -> Result<()> {
+        let token_pools = &mut ctx.accounts.token_pools;
+        require!(token_pools.launched == 0, CustomError::LaunchGraduated);
+
+        let expected_quote = token_pools.expected_quote_balance();
+        let actual_quote = match &ctx.accounts.quote_vault {
+            Some(quote_vault) => quote_vault.amount,
+            None => escrow_balance(&ctx.accounts.escrow_account),
+        };
+        let expected_tokens = token_pools.expected_token_balance();
+        let actual_tokens = ctx.accounts.token_vault.amount;
+
+        // Surpluses are not flagged, anyone can send lamports or tokens to the vaults
+        if actual_quote < expected_quote || actual_tokens < expected_tokens {
+            if ctx.accounts.global_info.audit_auto_pause {
+                token_pools.paused = true;
+            }
+            emit!(PoolAuditFailed {
+                mint: ctx.accounts.mint.key(),
+                expected_quote,
+                actual_quote,
+                expected_tokens,
+                actual_tokens,
+                paused: token_pools.paused,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn set_pool_paused(ctx: Context<SetPoolPaused>, paused: bool) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.token_pools.paused = paused;
+        Ok(())
+    }
+
+    // Holds a name for NAME_RESERVATION_SECONDS, it can be taken again once that lapses.
+    // The last holder sits out a cooldown first, so one wallet cannot hold a name forever.
+    pub fn reserve_name(ctx: Context<ReserveName>, name: String) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let name_record = &mut ctx.accounts.name_record;
+        require!(
+            name_record.mint == Pubkey::default(),
+            CustomError::NameAlreadyUsed
+        );
+        require!(
+            name_record.owner != ctx.accounts.owner.key()
+                || now >= name_record.expires_at + NAME_RESERVATION_COOLDOWN_SECONDS,
+            CustomError::NameReservationCooldown
+        );
+        require!(
+            name_record.owner == Pubkey::default() || now >= name_record.expires_at,
+            CustomError::NameReserved
+        );
+        name_record.owner = ctx.accounts.owner.key();
+        name_record.name_hash = name_hash(&name);
+        name_record.expires_at = now + NAME_RESERVATION_SECONDS;
+        Ok(())
+    }
+
+    pub fn adopt_global_config(ctx: Context<AdoptGlobalConfig>) -> Result<()> {
+        require!(
+            ctx.accounts.token_pools.creator == ctx.accounts.creator.key(),
+            CustomError::NotCreator
+        );
+        ctx.accounts
+            .token_pools
+            .snapshot_fee_config(&ctx.accounts.global_info);
+        Ok(())
+    }
+
+    pub fn withdraw_balance(ctx: Context<WithdrawBalance>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        let pool_amount = match &ctx.accounts.quote_vault {
+            Some(quote_vault) => quote_vault.amount,
+            None => escrow_balance(&ctx.accounts.escrow_account),
+        };
+        require!(pool_amount >= amount, CustomError::InvalidSolAmount);
+
+        let quote = ctx.accounts.token_pools.quote_accounts(
+            ctx.accounts.admin_quote_account.as_deref(),
+            ctx.accounts.quote_vault.as_deref(),
+            ctx.accounts.quote_token_program.as_ref(),
+        )?;
+        let binding = ctx.accounts.mint.key();
+        let vault_seeds = &[
+            QUOTE_VAULT_SEED.as_bytes(),
+            binding.as_ref(),
+            &[ctx.bumps.quote_vault],
+        ];
+        withdraw_quote(
+            &ctx.accounts.escrow_account,
+            ctx.accounts.admin.to_account_info(),
+            quote,
+            vault_seeds,
+            amount,
+        )?;
+
+        // Collected fees are withdrawn first, anything above them comes out of the curve reserve
+        let token_pools = &mut ctx.accounts.token_pools;
+        token_pools.accumulate_price(Clock::get()?.unix_timestamp);
+        let from_fees = amount.min(token_pools.fees_collected);
+        token_pools.fees_collected -= from_fees;
+        token_pools.sol_reserve = token_pools
+            .sol_reserve
+            .checked_sub(amount - from_fees)
+            .ok_or(CustomError::InvalidSolAmount)?;
+        token_pools.last_price = token_pools.spot_price()?;
+        Ok(())
+    }
+
+    pub fn set_fee_percent(ctx: Context<CommonCtx>, fee_percent: u32) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.fee_percent = fee_percent;
+        Ok(())
+    }
+
+    pub fn set_target_market_cap(ctx: Context<CommonCtx>, target_market_cap: u64) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.target_market_cap = target_market_cap;
+        Ok(())
+    }
+
+    pub fn set_target_lp_amount(ctx: Context<CommonCtx>, target_lp_amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
             CustomError::NotOwner
         );
         ctx.accounts.global_info.target_lp_amount = target_lp_amount;
         Ok(())
     }
 
+    // In whole tokens, each launch scales it by its own decimals
     pub fn set_total_supply(ctx: Context<CommonCtx>, total_supply: u64) -> Result<()> {
         require!(
             ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
@@ -341,115 +2157,1080 @@ mod token_minter {
         ctx.accounts.global_info.initial_amount = initial_amount;
         Ok(())
     }
+
+    pub fn add_quote_mint(
+        ctx: Context<CommonCtx>,
+        quote_mint: Pubkey,
+        initial_amount: u64,
+        usd_pegged: bool,
+        max_buy_amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        let quote_mints = &mut ctx.accounts.global_info.quote_mints;
+        let index = quote_mints
+            .iter()
+            .position(|q| q.mint == quote_mint)
+            .or_else(|| quote_mints.iter().position(|q| q.mint == Pubkey::default()))
+            .ok_or(CustomError::QuoteMintListFull)?;
+        quote_mints[index] = QuoteMintConfig {
+            mint: quote_mint,
+            initial_amount,
+            usd_pegged,
+            max_buy_amount,
+        };
+        Ok(())
+    }
+
+    pub fn remove_quote_mint(ctx: Context<CommonCtx>, quote_mint: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        let quote_mints = &mut ctx.accounts.global_info.quote_mints;
+        let index = quote_mints
+            .iter()
+            .position(|q| q.mint == quote_mint)
+            .ok_or(CustomError::QuoteMintNotAllowed)?;
+        quote_mints[index] = QuoteMintConfig::default();
+        Ok(())
+    }
+
+    pub fn harvest_transfer_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, HarvestTransferFees<'info>>,
+    ) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+
+        // Fees withheld in holder accounts, passed as remaining accounts, are swept into the mint.
+        // The recipients are swept too, so the fee withheld on their payouts below is all that
+        // is left in them afterwards.
+        let recipients = [
+            ctx.accounts.creator_token_account.to_account_info(),
+            ctx.accounts.protocol_token_account.to_account_info(),
+        ];
+        let sources: Vec<&Pubkey> = recipients
+            .iter()
+            .chain(ctx.remaining_accounts)
+            .map(|a| a.key)
+            .collect();
+        let mut account_infos = vec![ctx.accounts.mint.to_account_info()];
+        account_infos.extend_from_slice(&recipients);
+        account_infos.extend_from_slice(ctx.remaining_accounts);
+        invoke(
+            &transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+                &ctx.accounts.token_program.key(),
+                &mint_key,
+                &sources,
+            )?,
+            &account_infos,
+        )?;
+
+        let authority_seeds = &[
+            TRANSFER_FEE_AUTHORITY_SEED.as_bytes(),
+            mint_key.as_ref(),
+            &[ctx.bumps.fee_authority],
+        ];
+        invoke_signed(
+            &transfer_fee::instruction::withdraw_withheld_tokens_from_mint(
+                &ctx.accounts.token_program.key(),
+                &mint_key,
+                &ctx.accounts.fee_vault.key(),
+                &ctx.accounts.fee_authority.key(),
+                &[],
+            )?,
+            &[
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.fee_vault.to_account_info(),
+                ctx.accounts.fee_authority.to_account_info(),
+            ],
+            &[&authority_seeds[..]],
+        )?;
+        ctx.accounts.fee_vault.reload()?;
+
+        let harvested = ctx.accounts.fee_vault.amount;
+        require!(harvested > 0, CustomError::NothingToClaim);
+        let creator_amount = mul_div(
+            harvested,
+            ctx.accounts.token_pools.creator_fee_share as u64,
+            10000,
+        )?;
+        let payouts = [
+            (recipients[0].clone(), creator_amount),
+            (recipients[1].clone(), harvested - creator_amount),
+        ];
+        for (recipient, amount) in payouts {
+            if amount == 0 {
+                continue;
+            }
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: ctx.accounts.fee_vault.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: recipient.clone(),
+                        authority: ctx.accounts.fee_authority.to_account_info(),
+                    },
+                    &[&authority_seeds[..]],
+                ),
+                amount,
+                ctx.accounts.mint.decimals,
+            )?;
+            // The payout is charged the transfer fee again, withdrawing what was withheld
+            // on it back into the same account makes the recipient whole
+            invoke_signed(
+                &transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+                    &ctx.accounts.token_program.key(),
+                    &mint_key,
+                    recipient.key,
+                    &ctx.accounts.fee_authority.key(),
+                    &[],
+                    &[recipient.key],
+                )?,
+                &[
+                    ctx.accounts.mint.to_account_info(),
+                    recipient.clone(),
+                    ctx.accounts.fee_authority.to_account_info(),
+                ],
+                &[&authority_seeds[..]],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_sniper_window_seconds(
+        ctx: Context<CommonCtx>,
+        sniper_window_seconds: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.sniper_window_seconds = sniper_window_seconds;
+        Ok(())
+    }
+
+    pub fn set_max_buy_amount(ctx: Context<CommonCtx>, max_buy_amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.max_buy_amount = max_buy_amount;
+        Ok(())
+    }
+
+    pub fn set_max_buy_percent(ctx: Context<CommonCtx>, max_buy_percent: u32) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.max_buy_percent = max_buy_percent;
+        Ok(())
+    }
+
+    pub fn set_collection_mint(ctx: Context<CommonCtx>, collection_mint: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.collection_mint = collection_mint;
+        Ok(())
+    }
+
+    pub fn set_max_creator_allocation(
+        ctx: Context<CommonCtx>,
+        max_creator_allocation: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        require!(
+            max_creator_allocation <= 10000,
+            CustomError::InvalidBasisPoints
+        );
+        ctx.accounts.global_info.max_creator_allocation = max_creator_allocation;
+        Ok(())
+    }
+
+    pub fn set_graduation_twap_window(
+        ctx: Context<CommonCtx>,
+        graduation_twap_window: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.graduation_twap_window = graduation_twap_window;
+        Ok(())
+    }
+
+    pub fn set_min_holder_count(ctx: Context<CommonCtx>, min_holder_count: u32) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.min_holder_count = min_holder_count;
+        Ok(())
+    }
+
+    pub fn set_max_holder_percent(ctx: Context<CommonCtx>, max_holder_percent: u32) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.max_holder_percent = max_holder_percent;
+        Ok(())
+    }
+
+    pub fn set_audit_auto_pause(ctx: Context<CommonCtx>, audit_auto_pause: bool) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        ctx.accounts.global_info.audit_auto_pause = audit_auto_pause;
+        Ok(())
+    }
+
+    pub fn set_creator_fee_share(ctx: Context<CommonCtx>, creator_fee_share: u32) -> Result<()> {
+        require!(
+            ctx.accounts.global_info.owner == ctx.accounts.admin.key(),
+            CustomError::NotOwner
+        );
+        require!(creator_fee_share <= 10000, CustomError::InvalidBasisPoints);
+        ctx.accounts.global_info.creator_fee_share = creator_fee_share;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump,
+        space = GLOBAL_INFO_SIZE
+    )]
+    pub global_info: Account<'info, GlobalInfo>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGlobalInfo<'info> {
+    /// CHECK: Read with the current layout once it is reallocated, see migrate_global_info
+    #[account(
+        mut,
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_info: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(launch_index: u32, creator: Pubkey)]
+pub struct MigrateTokenPools<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Read with the current layout once it is reallocated, see migrate_token_pools
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: UncheckedAccount<'info>,
+    /// CHECK: Old escrows hold no data, migrate_token_pools writes the SolEscrow layout
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub escrow_account: UncheckedAccount<'info>,
+    #[account(
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump
+    )]
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LAUNCH_SEED.as_bytes(), launch_index.to_le_bytes().as_ref()],
+        bump,
+        space = LAUNCH_RECORD_SIZE
+    )]
+    pub launch: Box<Account<'info, LaunchRecord>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [CREATOR_INFO_SEED.as_bytes(), creator.as_ref()],
+        bump,
+        space = CREATOR_INFO_SIZE
+    )]
+    pub creator_info: Box<Account<'info, CreatorInfo>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CANDLES_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = CANDLE_BUFFER_SIZE
+    )]
+    pub candles: AccountLoader<'info, CandleBuffer>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    params: InitTokenParams
+)]
+pub struct InitToken<'info> {
+    #[account(
+        mut,
+        seeds = [NAME_RECORD_SEED.as_bytes(), name_hash(&params.name).as_ref()],
+        bump,
+    )]
+    pub name_record: Option<Box<Account<'info, NameRecord>>>,
+    /// CHECK: New Metaplex Account being created, not used by Token-2022 launches
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: Platform collection mint, must match global_info.collection_mint
+    pub collection_mint: Option<UncheckedAccount<'info>>,
+    /// CHECK: Metaplex metadata of the platform collection, validated by the metadata program
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: Metaplex master edition of the platform collection, validated by the metadata program
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
+    /// CHECK: PDA holding the collection's update authority, signs collection verification
+    #[account(
+        seeds = [COLLECTION_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub collection_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: Created and initialized in create_token under the selected token program
+    #[account(
+        mut,
+        seeds = [MINT_SEED.as_bytes(), global_info.token_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: Payer's associated token account, created by the associated token program
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: Created and initialized in create_token, the PDA address is both the vault account and the authority
+    #[account(
+        mut,
+        seeds = [ mint.key().as_ref() ],
+        bump,
+    )]
+    pub token_vault: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+        space = SOL_ESCROW_SIZE
+    )]
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump
+    )]
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [LAUNCH_SEED.as_bytes(), global_info.token_count.to_le_bytes().as_ref()],
+        bump,
+        space = LAUNCH_RECORD_SIZE
+    )]
+    pub launch: Box<Account<'info, LaunchRecord>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [CREATOR_INFO_SEED.as_bytes(), payer.key().as_ref()],
+        bump,
+        space = CREATOR_INFO_SIZE
+    )]
+    pub creator_info: Box<Account<'info, CreatorInfo>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [VESTING_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = VESTING_SIZE
+    )]
+    pub vesting: Option<Box<Account<'info, CreatorVesting>>>,
+    /// CHECK: Created and initialized in create_token, the vesting PDA is its authority
+    #[account(
+        mut,
+        seeds = [VESTING_VAULT_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub vesting_vault: Option<UncheckedAccount<'info>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [AIRDROP_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = AIRDROP_SIZE + params.airdrop.as_ref().map_or(0, |a| a.leaf_count as usize / 8 + 1)
+    )]
+    pub airdrop: Option<Box<Account<'info, Airdrop>>>,
+    /// CHECK: Created and initialized in create_token, the airdrop PDA is its authority
+    #[account(
+        mut,
+        seeds = [AIRDROP_VAULT_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub airdrop_vault: Option<UncheckedAccount<'info>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [CANDLES_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = CANDLE_BUFFER_SIZE
+    )]
+    pub candles: AccountLoader<'info, CandleBuffer>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POSITION_SEED.as_bytes(), mint.key().as_ref(), payer.key().as_ref()],
+        bump,
+        space = POSITION_SIZE
+    )]
+    pub position: Option<Box<Account<'info, Position>>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = TOKEN_POOL_SIZE
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    pub quote_mint: Option<Box<Account<'info, token::Mint>>>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = quote_mint,
+        token::authority = quote_vault,
+        token::token_program = quote_token_program,
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub payer_quote_account: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+    pub token_metadata_program: Option<Program<'info, Metaplex>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct BuyToken<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = token_vault, //the PDA address is both the vault account and the authority (and event the mint authority)
+        seeds = [ mint.key().as_ref() ],
+        bump,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump
+    )]
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program,
+    )]
+    pub buyer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        seeds = [BUYER_INFO_SEED.as_bytes(), mint.key().as_ref(), buyer.key().as_ref()],
+        bump,
+        space = BUYER_INFO_SIZE
+    )]
+    pub buyer_info: Box<Account<'info, BuyerInfo>>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        seeds = [POSITION_SEED.as_bytes(), mint.key().as_ref(), buyer.key().as_ref()],
+        bump,
+        space = POSITION_SIZE
+    )]
+    pub position: Option<Box<Account<'info, Position>>>,
+    #[account(
+        mut,
+        seeds = [CANDLES_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub candles: AccountLoader<'info, CandleBuffer>,
+    #[account(
+        mut,
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub buyer_quote_account: Option<Box<Account<'info, token::TokenAccount>>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct SellToken<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = token_vault,
+        seeds = [ mint.key().as_ref() ],
+        bump,
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump
+    )]
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(mut)]
+    pub seller_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        seeds = [POSITION_SEED.as_bytes(), mint.key().as_ref(), seller.key().as_ref()],
+        bump,
+        space = POSITION_SIZE
+    )]
+    pub position: Option<Box<Account<'info, Position>>>,
+    #[account(
+        mut,
+        seeds = [CANDLES_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub candles: AccountLoader<'info, CandleBuffer>,
+    #[account(
+        mut,
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub seller_quote_account: Option<Box<Account<'info, token::TokenAccount>>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+pub struct CommitAuction<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
     #[account(
         init_if_needed,
-        payer = owner,
+        payer = buyer,
+        seeds = [AUCTION_COMMITMENT_SEED.as_bytes(), mint.key().as_ref(), buyer.key().as_ref()],
+        bump,
+        space = AUCTION_COMMITMENT_SIZE
+    )]
+    pub commitment: Box<Account<'info, AuctionCommitment>>,
+    #[account(
+        mut,
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub buyer_quote_account: Option<Box<Account<'info, token::TokenAccount>>>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
         seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump
+    )]
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
         bump,
-        space = GLOBAL_INFO_SIZE
     )]
-    pub global_info: Account<'info, GlobalInfo>,
+    pub token_pools: Box<Account<'info, TokenPools>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub beneficiary: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        mut,
+        has_one = beneficiary,
+        has_one = mint,
+        seeds = [VESTING_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Box<Account<'info, CreatorVesting>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = vesting,
+        seeds = [VESTING_VAULT_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub vesting_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        seeds = [POSITION_SEED.as_bytes(), mint.key().as_ref(), beneficiary.key().as_ref()],
+        bump,
+        space = POSITION_SIZE
+    )]
+    pub position: Option<Box<Account<'info, Position>>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[derive(Accounts)]
-#[instruction(
-    params: InitTokenParams
-)]
-pub struct InitToken<'info> {
-    /// CHECK: New Metaplex Account being created
+pub struct ClaimAirdrop<'info> {
     #[account(mut)]
-    pub metadata: UncheckedAccount<'info>,
+    pub claimer: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
-        init,
-        seeds = [MINT_SEED.as_bytes(), params.name.as_bytes()],
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [AIRDROP_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub airdrop: Box<Account<'info, Airdrop>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = airdrop,
+        seeds = [AIRDROP_VAULT_SEED.as_bytes(), mint.key().as_ref()],
         bump,
-        payer = payer,
-        mint::decimals = params.decimals,
-        mint::authority = mint,
     )]
-    pub mint: Box<Account<'info, Mint>>,
+    pub airdrop_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         init_if_needed,
-        payer = payer,
+        payer = claimer,
         associated_token::mint = mint,
-        associated_token::authority = payer,
+        associated_token::authority = claimer,
+        associated_token::token_program = token_program,
     )]
-    pub destination: Box<Account<'info, TokenAccount>>,
+    pub claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         init_if_needed,
-        payer = payer,
+        payer = claimer,
+        seeds = [POSITION_SEED.as_bytes(), mint.key().as_ref(), claimer.key().as_ref()],
+        bump,
+        space = POSITION_SIZE
+    )]
+    pub position: Option<Box<Account<'info, Position>>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAirdrop<'info> {
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [AIRDROP_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub airdrop: Box<Account<'info, Airdrop>>,
+    #[account(
+        mut,
         token::mint = mint,
-        token::authority = token_vault, //the PDA address is both the vault account and the authority (and event the mint authority)
+        token::authority = airdrop,
+        seeds = [AIRDROP_VAULT_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub airdrop_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAuction<'info> {
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = token_vault,
         seeds = [ mint.key().as_ref() ],
         bump,
     )]
-    pub token_vault: Box<Account<'info, TokenAccount>>,
-    /// CHECK: This is vault account.
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        mut,
+        seeds = [AUCTION_COMMITMENT_SEED.as_bytes(), mint.key().as_ref(), claimer.key().as_ref()],
+        bump,
+    )]
+    pub commitment: Box<Account<'info, AuctionCommitment>>,
     #[account(
         init_if_needed,
-        payer = payer,
+        payer = claimer,
+        associated_token::mint = mint,
+        associated_token::authority = claimer,
+        associated_token::token_program = token_program,
+    )]
+    pub claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        seeds = [POSITION_SEED.as_bytes(), mint.key().as_ref(), claimer.key().as_ref()],
+        bump,
+        space = POSITION_SIZE
+    )]
+    pub position: Option<Box<Account<'info, Position>>>,
+    #[account(
+        mut,
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub claimer_quote_account: Option<Box<Account<'info, token::TokenAccount>>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBalance<'info> {
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
         seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
         bump,
-        space = 8 + 8
     )]
-    pub escrow_account: UncheckedAccount<'info>,
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Account<'info, TokenPools>,
     #[account(
         mut,
         seeds = [GLOBAL_INFO_SEED.as_bytes()],
         bump
     )]
-    pub global_info: Box<Account<'info, GlobalInfo>>,
+    pub global_info: Account<'info, GlobalInfo>,
+    #[account(
+        mut,
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub admin_quote_account: Option<Box<Account<'info, token::TokenAccount>>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    // Migrated launches keep name seeded mints and fail these seeds, see migrate_token_pools
+    /// CHECK: The mint PDA, update authority of its own metadata, holds it for Token-2022 launches
+    #[account(
+        mut,
+        seeds = [MINT_SEED.as_bytes(), token_pools.launch_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub mint: UncheckedAccount<'info>,
+    // Metaplex launches only, Token-2022 launches pass token_program instead
+    #[account(
+        mut,
+        seeds = [b"metadata", Metaplex::id().as_ref(), mint.key().as_ref()],
+        seeds::program = Metaplex::id(),
+        bump,
+    )]
+    pub metadata: Option<Box<Account<'info, MetadataAccount>>>,
     #[account(
-        init_if_needed,
-        payer = payer,
         seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
         bump,
-        space = TOKEN_POOL_SIZE
     )]
     pub token_pools: Box<Account<'info, TokenPools>>,
     #[account(mut)]
-    pub payer: Signer<'info>,
-    pub rent: Sysvar<'info, Rent>,
+    pub creator: Signer<'info>,
+    pub token_metadata_program: Option<Program<'info, Metaplex>>,
+    pub token_program: Option<Program<'info, Token2022>>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub token_metadata_program: Program<'info, Metaplex>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> UpdateMetadata<'info> {
+    // The mint PDA signs as update authority
+    pub fn update_ctx<'a, 'b, 'c>(
+        &self,
+        signer: &'a [&'b [&'c [u8]]],
+    ) -> Result<CpiContext<'a, 'b, 'c, 'info, UpdateMetadataAccountsV2<'info>>> {
+        let (Some(metadata), Some(token_metadata_program)) =
+            (&self.metadata, &self.token_metadata_program)
+        else {
+            return err!(CustomError::MissingMetadataAccounts);
+        };
+        Ok(CpiContext::new_with_signer(
+            token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: metadata.to_account_info(),
+                update_authority: self.mint.to_account_info(),
+            },
+            signer,
+        ))
+    }
+
+    // The token program reallocs the mint for longer values, the creator tops up its rent first
+    pub fn update_token_2022_fields(
+        &self,
+        fields: Vec<(Field, String)>,
+        signer: &[&[&[u8]]],
+    ) -> Result<()> {
+        let token_program = self
+            .token_program
+            .as_ref()
+            .ok_or(CustomError::MissingMetadataAccounts)?;
+        let mint = self.mint.to_account_info();
+        let new_len = {
+            let data = mint.try_borrow_data()?;
+            let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+            let mut token_metadata = state.get_variable_len_extension::<TokenMetadata>()?;
+            let old_size = token_metadata.tlv_size_of()?;
+            for (field, value) in fields.iter() {
+                token_metadata.update(field.clone(), value.clone());
+            }
+            data.len() - old_size + token_metadata.tlv_size_of()?
+        };
+        let rent_due = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(mint.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.creator.to_account_info(),
+                        to: mint.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        for (field, value) in fields {
+            invoke_signed(
+                &spl_token_metadata_interface::instruction::update_field(
+                    &token_program.key(),
+                    &mint.key(),
+                    &mint.key(),
+                    field,
+                    value,
+                ),
+                std::slice::from_ref(&mint),
+                signer,
+            )?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
-pub struct BuyToken<'info> {
-    #[account(mut)]
-    pub buyer: Signer<'info>,
+pub struct SetLaunchProfile<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [LAUNCH_PROFILE_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = LAUNCH_PROFILE_SIZE
+    )]
+    pub launch_profile: Box<Account<'info, LaunchProfile>>,
     #[account(mut)]
-    pub mint: Box<Account<'info, Mint>>,
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CheckpointTwap<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        token::mint = mint,
-        token::authority = token_vault, //the PDA address is both the vault account and the authority (and event the mint authority)
-        seeds = [ mint.key().as_ref() ],
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+}
+
+#[derive(Accounts)]
+pub struct AuditPool<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump
+    )]
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    #[account(
+        seeds = [mint.key().as_ref()],
         bump,
     )]
-    pub token_vault: Box<Account<'info, TokenAccount>>,
-    /// CHECK: This is vault account.
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-        mut,
         seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
         bump,
     )]
-    pub escrow_account: UncheckedAccount<'info>,
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
     #[account(
-        mut,
-        seeds = [GLOBAL_INFO_SEED.as_bytes()],
-        bump
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
     )]
-    pub global_info: Box<Account<'info, GlobalInfo>>,
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolPaused<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
         seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
@@ -457,40 +3238,60 @@ pub struct BuyToken<'info> {
     )]
     pub token_pools: Box<Account<'info, TokenPools>>,
     #[account(
-        init_if_needed,
-        payer = buyer,
-        associated_token::mint = mint,
-        associated_token::authority = buyer,
+        seeds = [GLOBAL_INFO_SEED.as_bytes()],
+        bump
     )]
-    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct SellToken<'info> {
-    #[account(mut)]
-    pub seller: Signer<'info>,
-    #[account(mut)]
-    pub mint: Box<Account<'info, Mint>>,
+pub struct CancelLaunch<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        token::mint = mint,
-        token::authority = token_vault,
-        seeds = [ mint.key().as_ref() ],
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
         bump,
     )]
-    pub token_vault: Box<Account<'info, TokenAccount>>,
-    /// CHECK: This is vault account.
+    pub token_pools: Box<Account<'info, TokenPools>>,
     #[account(
         mut,
-        seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        has_one = mint,
+        seeds = [LAUNCH_SEED.as_bytes(), token_pools.launch_index.to_le_bytes().as_ref()],
         bump,
     )]
-    pub escrow_account: UncheckedAccount<'info>,
+    pub launch: Box<Account<'info, LaunchRecord>>,
     #[account(
         mut,
+        seeds = [CREATOR_INFO_SEED.as_bytes(), creator.key().as_ref()],
+        bump,
+    )]
+    pub creator_info: Box<Account<'info, CreatorInfo>>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    name: String
+)]
+pub struct ReserveName<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [NAME_RECORD_SEED.as_bytes(), name_hash(&name).as_ref()],
+        bump,
+        space = NAME_RECORD_SIZE
+    )]
+    pub name_record: Box<Account<'info, NameRecord>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdoptGlobalConfig<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
         seeds = [GLOBAL_INFO_SEED.as_bytes()],
         bump
     )]
@@ -501,37 +3302,54 @@ pub struct SellToken<'info> {
         bump,
     )]
     pub token_pools: Box<Account<'info, TokenPools>>,
-    #[account(mut)]
-    pub seller_token_account: Box<Account<'info, TokenAccount>>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    pub creator: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawBalance<'info> {
+pub struct HarvestTransferFees<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    /// CHECK: This is vault account.
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: PDA set as the mint's withdraw-withheld authority, only signs fee withdrawals
     #[account(
-        mut,
-        seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        seeds = [TRANSFER_FEE_AUTHORITY_SEED.as_bytes(), mint.key().as_ref()],
         bump,
     )]
-    pub escrow_account: UncheckedAccount<'info>,
+    pub fee_authority: UncheckedAccount<'info>,
     #[account(
-        mut,
-        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        init_if_needed,
+        payer = payer,
+        token::mint = mint,
+        token::authority = fee_authority,
+        token::token_program = token_program,
+        seeds = [TRANSFER_FEE_VAULT_SEED.as_bytes(), mint.key().as_ref()],
         bump,
     )]
-    pub token_pools: Account<'info, TokenPools>,
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-        mut,
         seeds = [GLOBAL_INFO_SEED.as_bytes()],
         bump
     )]
-    pub global_info: Account<'info, GlobalInfo>,
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    pub global_info: Box<Account<'info, GlobalInfo>>,
+    #[account(
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub token_pools: Box<Account<'info, TokenPools>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = token_pools.creator,
+    )]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = global_info.owner,
+    )]
+    pub protocol_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
 }
 
@@ -551,18 +3369,25 @@ pub struct CommonCtx<'info> {
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
     #[account()]
-    pub mint: Box<Account<'info, Mint>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
     /// CHECK: Safe. The user coin token
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint,
         associated_token::authority = payer,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_coin: Box<Account<'info, TokenAccount>>,
+    pub user_token_coin: Box<InterfaceAccount<'info, TokenAccount>>,
     /// CHECK: Safe. The user pc token
     #[account(mut)]
     pub user_token_pc: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [ QUOTE_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
+        bump,
+    )]
+    pub quote_vault: Option<Box<Account<'info, token::TokenAccount>>>,
     #[account(
         mut,
         token::mint = mint,
@@ -570,21 +3395,38 @@ pub struct AddLiquidity<'info> {
         seeds = [ mint.key().as_ref() ],
         bump,
     )]
-    pub token_vault: Box<Account<'info, TokenAccount>>,
-    /// CHECK: This is vault account.
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut,
         seeds = [ SOL_VAULT_SEED.as_bytes(), mint.key().as_ref() ],
         bump,
     )]
-    pub escrow_account: UncheckedAccount<'info>,
+    pub escrow_account: Box<Account<'info, SolEscrow>>,
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(mut)]
     pub global_info: Box<Account<'info, GlobalInfo>>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [TOKEN_POOL_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
     pub token_pools: Box<Account<'info, TokenPools>>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [LAUNCH_SEED.as_bytes(), token_pools.launch_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub launch: Box<Account<'info, LaunchRecord>>,
+    #[account(
+        mut,
+        seeds = [CREATOR_INFO_SEED.as_bytes(), token_pools.creator.as_ref()],
+        bump,
+    )]
+    pub creator_info: Box<Account<'info, CreatorInfo>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Option<Program<'info, Token>>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
@@ -596,6 +3438,73 @@ pub struct InitTokenParams {
     pub symbol: String,
     pub uri: String,
     pub decimals: u8,
+    pub trading_starts_at: i64, // unix timestamp, buys and sells are rejected before it
+    pub batch_auction: Option<BatchAuctionParams>, // None -> bonding curve launch
+    pub dutch_auction: Option<DutchAuctionParams>, // None -> bonding curve launch
+    pub transfer_fee: Option<TransferFeeParams>, // Token-2022 only, None -> no transfer fee
+    pub vesting: Option<VestingParams>, // None -> no creator allocation
+    pub airdrop: Option<AirdropParams>, // None -> no airdrop allocation
+    pub allowlist: Option<AllowlistParams>, // None -> public from trading_starts_at
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct AllowlistParams {
+    pub root: [u8; 32], // leaves are keccak(wallet || allocation)
+    pub ends_at: i64,   // public trading opens at this unix timestamp
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct AirdropParams {
+    pub amount: u64,           // in token base units, taken off the curve supply
+    pub merkle_root: [u8; 32], // leaves are keccak(index || wallet || amount)
+    pub leaf_count: u32,
+    pub expires_at: i64, // unclaimed tokens can be burned from this unix timestamp
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct VestingParams {
+    pub amount: u64,           // in token base units, capped by max_creator_allocation
+    pub cliff_seconds: i64,    // nothing can be claimed before the cliff
+    pub duration_seconds: i64, // linear release from creation until the end
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TransferFeeParams {
+    pub basis_points: u16, // withheld on every transfer, 1% = 100
+    pub maximum_fee: u64,  // per transfer, in token base units
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct BatchAuctionParams {
+    pub ends_at: i64, // commitments are accepted from trading_starts_at until this timestamp
+    pub supply: u64,  // tokens offered in the auction
+    pub max_price: u64, // clearing price cap per whole token, 0 -> uncapped
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct DutchAuctionParams {
+    pub start_slot: u64,
+    pub start_price: u64, // per whole token
+    pub floor_price: u64,
+    pub decay: u8,       // 0 -> linear, 1 -> exponential
+    pub decay_rate: u64, // price drop per slot, or 1% = 100 per slot when exponential
+    pub supply: u64,     // tokens offered in the auction
+    pub end_slot: u64,   // the auction ends here or when sold out, trading continues on the curve
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct LaunchProfileParams {
+    pub description: String,
+    pub website: String,
+    pub twitter: String,
+    pub telegram: String,
+    pub image_hash: [u8; 32], // sha256 of the image behind the metadata uri
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct AllowlistProof {
+    pub allocation: u64, // quote base units the wallet may spend during the allowlist phase
+    pub proof: Vec<[u8; 32]>,
 }
 
 #[account]
@@ -603,18 +3512,568 @@ pub struct GlobalInfo {
     pub fee_percent: u32,
     pub target_market_cap: u64,
     pub target_lp_amount: u64,
-    pub total_supply: u64,
+    pub total_supply: u64, // whole tokens, scaled by the decimals of each launch
     pub initial_amount: u64,
     pub token_count: u32,
     pub liquidity_added: bool,
     pub owner: Pubkey,
+    pub sniper_window_seconds: i64, // from public_trading_starts_at
+    pub max_buy_amount: u64, // lamports per wallet during the sniper window, 0 -> no cap, see QuoteMintConfig
+    pub max_buy_percent: u32, // of total supply per wallet during the sniper window, 1% = 100
+    pub quote_mints: [QuoteMintConfig; MAX_QUOTE_MINTS], // allowlisted SPL quote mints
+    pub creator_fee_share: u32, // of harvested transfer fees, 1% = 100, rest goes to the protocol
+    pub collection_mint: Pubkey, // platform Metaplex collection, Pubkey::default() -> none
+    pub max_creator_allocation: u32, // of total supply, 1% = 100
+    pub graduation_twap_window: i64, // seconds, 0 -> graduation is not gated on the TWAP
+    pub min_holder_count: u32, // 0 -> graduation is not gated on holders
+    pub max_holder_percent: u32, // largest holder's share at graduation, 1% = 100, 0 -> no cap
+    pub audit_auto_pause: bool, // audit_pool pauses pools it finds underfunded
+    pub version: u8,         // see GLOBAL_INFO_VERSION
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, Default)]
+pub struct QuoteMintConfig {
+    pub mint: Pubkey,
+    pub initial_amount: u64, // virtual quote reserve of new pools, in quote base units
+    pub usd_pegged: bool,    // stablecoins are valued at 1 USD at graduation
+    pub max_buy_amount: u64, // per wallet during the sniper window, in quote base units, 0 -> no cap
 }
 
 #[account]
 pub struct TokenPools {
-    pub sol_reserve: u64,
+    pub sol_reserve: u64, // in quote base units for quote-token launches
     pub token_reserve: u64,
     pub launched: u8, // 0 -> false, 1 -> true
+    pub trading_starts_at: i64,
+    pub creator: Pubkey,
+    pub allowlist_root: [u8; 32],
+    pub allowlist_ends_at: i64, // public trading opens at this unix timestamp
+    pub launch_mode: u8,        // 0 -> bonding curve, 1 -> batch auction, 2 -> dutch auction
+    pub auction_ends_at: i64,
+    pub auction_supply: u64,
+    pub auction_max_price: u64,
+    pub auction_committed: u64,
+    pub auction_price: u64,
+    pub auction_sold: u64,
+    pub auction_raised: u64, // batch: quote that cleared, dutch: proceeds net of fees
+    pub auction_settled: bool, // dutch: ended and folded into the curve
+    pub dutch_start_slot: u64,
+    pub dutch_start_price: u64,
+    pub dutch_floor_price: u64,
+    pub dutch_decay: u8, // 0 -> linear, 1 -> exponential
+    pub dutch_decay_rate: u64,
+    pub dutch_end_slot: u64,
+    pub quote_mint: Pubkey, // Pubkey::default() -> native SOL
+    pub quote_decimals: u8,
+    pub quote_usd_pegged: bool,
+    pub quote_max_buy_amount: u64, // sniper cap of the quote mint at creation, removal keeps it
+    pub total_supply: u64,         // in base units of this mint, fixed at creation
+    pub decimals: u8,
+    pub fee_percent: u32, // snapshots of global_info, see snapshot_config
+    pub target_market_cap: u64,
+    pub target_lp_amount: u64,
+    pub creator_fee_share: u32,
+    pub launch_index: u32, // seed of the launch registry entry
+    pub cancelled: bool,
+    pub graduation_twap_window: i64,
+    pub price_cumulative: u128, // sum of last_price * seconds, wraps like Uniswap v2
+    pub price_updated_at: i64,
+    pub last_price: u64, // price of the last trade, see refresh_price
+    pub twap_checkpoint_cumulative: u128,
+    pub twap_checkpoint_at: i64,
+    pub prev_twap_checkpoint_cumulative: u128,
+    pub prev_twap_checkpoint_at: i64,
+    pub min_holder_count: u32,
+    pub max_holder_percent: u32,
+    pub holder_count: u32,        // positions with a non-zero curve balance
+    pub holder_supply: u64,       // sum of those balances
+    pub virtual_sol_reserve: u64, // part of sol_reserve that was never deposited
+    pub fees_collected: u64,      // quote fees held in the escrow outside sol_reserve
+    pub auction_refunded: u64,
+    pub auction_claimed: u64, // batch auction tokens already paid out
+    pub paused: bool,
+    pub version: u8, // see TOKEN_POOL_VERSION
+}
+
+#[event]
+pub struct PoolAuditFailed {
+    pub mint: Pubkey,
+    pub expected_quote: u64,
+    pub actual_quote: u64,
+    pub expected_tokens: u64,
+    pub actual_tokens: u64,
+    pub paused: bool,
+}
+
+// SPL accounts used to move quote tokens in and out of a quote-token launch
+pub struct QuoteTokenAccounts<'info> {
+    pub user_account: AccountInfo<'info>,
+    pub vault: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+}
+
+impl TokenPools {
+    // Copies the fee and graduation defaults, supply and curve stay fixed for the pool's lifetime
+    pub fn snapshot_config(&mut self, global_info: &GlobalInfo) {
+        self.snapshot_fee_config(global_info);
+        self.graduation_twap_window = global_info.graduation_twap_window;
+        self.min_holder_count = global_info.min_holder_count;
+        self.max_holder_percent = global_info.max_holder_percent;
+    }
+
+    // The part of the snapshot a live pool may adopt. Graduation gates stay as they were at
+    // creation, holders and the TWAP were built up against them.
+    pub fn snapshot_fee_config(&mut self, global_info: &GlobalInfo) {
+        self.fee_percent = global_info.fee_percent;
+        self.target_market_cap = global_info.target_market_cap;
+        self.target_lp_amount = global_info.target_lp_amount;
+        self.creator_fee_share = global_info.creator_fee_share;
+    }
+
+    // Sniper buy cap in the quote units of the pool, lamports for native SOL launches
+    pub fn max_buy_amount(&self, global_info: &GlobalInfo) -> u64 {
+        if self.quote_mint == Pubkey::default() {
+            return global_info.max_buy_amount;
+        }
+        self.quote_max_buy_amount
+    }
+
+    pub fn real_sol_reserve(&self) -> u64 {
+        self.sol_reserve.saturating_sub(self.virtual_sol_reserve)
+    }
+
+    // Quote the escrow or quote vault must hold: the deposited reserve, unwithdrawn fees
+    // and auction commitments that are not yet settled or refunded
+    pub fn expected_quote_balance(&self) -> u64 {
+        let pending_auction = match (self.launch_mode, self.auction_settled) {
+            (LAUNCH_MODE_BATCH_AUCTION, true) => {
+                self.auction_committed - self.auction_raised - self.auction_refunded
+            }
+            (LAUNCH_MODE_BATCH_AUCTION, false) => self.auction_committed,
+            (LAUNCH_MODE_DUTCH_AUCTION, false) => self.auction_raised,
+            _ => 0,
+        };
+        self.real_sol_reserve() + self.fees_collected + pending_auction
+    }
+
+    // Tokens the vault must hold: the curve reserve and batch auction tokens that were sold
+    // but not claimed yet. Running dutch auctions have paid out tokens that still count in the
+    // reserve. Vesting and airdrop allocations sit in vaults of their own.
+    pub fn expected_token_balance(&self) -> u64 {
+        match (self.launch_mode, self.auction_settled) {
+            (LAUNCH_MODE_BATCH_AUCTION, true) => {
+                self.token_reserve + self.auction_sold - self.auction_claimed
+            }
+            (LAUNCH_MODE_DUTCH_AUCTION, false) => self.token_reserve - self.auction_sold,
+            _ => self.token_reserve,
+        }
+    }
+
+    pub fn holder_gated(&self) -> bool {
+        self.min_holder_count > 0 || self.max_holder_percent > 0
+    }
+
+    // Keeps holder stats in step with a position balance change
+    pub fn record_holder(&mut self, balance_before: u64, balance_after: u64) {
+        if balance_before == 0 && balance_after > 0 {
+            self.holder_count += 1;
+        } else if balance_before > 0 && balance_after == 0 {
+            self.holder_count -= 1;
+        }
+        self.holder_supply = self.holder_supply - balance_before + balance_after;
+    }
+
+    // Credits tokens paid out outside a trade, holder gated pools need a position for every
+    // payout or the stats would miss tokens that are already in circulation
+    pub fn credit_position(
+        &mut self,
+        position: Option<&mut Position>,
+        owner: Pubkey,
+        mint: Pubkey,
+        sol_spent: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        match position {
+            Some(position) => {
+                position.bind(owner, mint);
+                let balance_before = position.token_balance;
+                position.record_claim(sol_spent, token_amount);
+                self.record_holder(balance_before, position.token_balance);
+            }
+            None => require!(!self.holder_gated(), CustomError::MissingPosition),
+        }
+        Ok(())
+    }
+
+    // Adds the last trade price held since the last update, call before reserves change
+    pub fn accumulate_price(&mut self, now: i64) {
+        if self.price_updated_at > 0 && now > self.price_updated_at {
+            self.price_cumulative = self
+                .price_cumulative
+                .wrapping_add(self.last_price as u128 * (now - self.price_updated_at) as u128);
+        }
+        self.price_updated_at = now;
+    }
+
+    pub fn spot_price(&self) -> Result<u64> {
+        get_price(self.sol_reserve, self.token_reserve, self.decimals)
+    }
+
+    // The TWAP holds the curve price after the reserves changed, or the execution price of a
+    // running dutch auction whose sales stay out of the reserves
+    pub fn refresh_price(&mut self, slot: u64, execution_price: u64) -> Result<()> {
+        self.last_price = if self.dutch_active(slot) {
+            execution_price
+        } else {
+            self.spot_price()?
+        };
+        Ok(())
+    }
+
+    // Rolls the checkpoint pair forward, at most once per window so the newest checkpoint
+    // cannot be kept younger than the window by calling this over and over
+    pub fn checkpoint_twap(&mut self, now: i64) -> Result<()> {
+        require!(
+            now - self.twap_checkpoint_at >= self.graduation_twap_window,
+            CustomError::TwapWindowNotElapsed
+        );
+        self.prev_twap_checkpoint_cumulative = self.twap_checkpoint_cumulative;
+        self.prev_twap_checkpoint_at = self.twap_checkpoint_at;
+        self.twap_checkpoint_cumulative = self.price_cumulative;
+        self.twap_checkpoint_at = now;
+        Ok(())
+    }
+
+    // Average price per whole token since the newest checkpoint that is at least one window old
+    pub fn graduation_twap(&self) -> Result<u64> {
+        let now = self.price_updated_at;
+        let (checkpoint_cumulative, checkpoint_at) =
+            if now - self.twap_checkpoint_at >= self.graduation_twap_window {
+                (self.twap_checkpoint_cumulative, self.twap_checkpoint_at)
+            } else {
+                (
+                    self.prev_twap_checkpoint_cumulative,
+                    self.prev_twap_checkpoint_at,
+                )
+            };
+        require!(
+            checkpoint_at > 0 && now - checkpoint_at >= self.graduation_twap_window,
+            CustomError::TwapWindowNotElapsed
+        );
+        let elapsed = now - checkpoint_at;
+        if elapsed <= 0 {
+            return Ok(0);
+        }
+        Ok((self.price_cumulative.wrapping_sub(checkpoint_cumulative) / elapsed as u128) as u64)
+    }
+
+    // Rejects trades before the scheduled start
+    pub fn open_trading(&self, clock: &Clock) -> Result<()> {
+        require!(!self.cancelled, CustomError::LaunchCancelled);
+        require!(!self.paused, CustomError::PoolPaused);
+        require!(
+            self.launch_mode != LAUNCH_MODE_BATCH_AUCTION || self.auction_settled,
+            CustomError::AuctionNotSettled
+        );
+        require!(
+            self.launch_mode != LAUNCH_MODE_DUTCH_AUCTION || clock.slot >= self.dutch_start_slot,
+            CustomError::TradingNotStarted
+        );
+        require!(
+            clock.unix_timestamp >= self.trading_starts_at,
+            CustomError::TradingNotStarted
+        );
+        Ok(())
+    }
+
+    // Allowlisted launches open to the public when the allowlist phase ends
+    pub fn public_trading_starts_at(&self) -> i64 {
+        if self.allowlist_root != [0u8; 32] {
+            self.trading_starts_at.max(self.allowlist_ends_at)
+        } else {
+            self.trading_starts_at
+        }
+    }
+
+    pub fn in_allowlist_phase(&self, clock: &Clock) -> bool {
+        self.allowlist_root != [0u8; 32] && clock.unix_timestamp < self.allowlist_ends_at
+    }
+
+    // Quote-token launches must provide the quote vault and the user's quote account,
+    // native SOL launches move lamports through the escrow instead
+    pub fn quote_accounts<'info, T: ToAccountInfo<'info>>(
+        &self,
+        user_account: Option<&T>,
+        vault: Option<&Account<'info, token::TokenAccount>>,
+        token_program: Option<&Program<'info, Token>>,
+    ) -> Result<Option<QuoteTokenAccounts<'info>>> {
+        if self.quote_mint == Pubkey::default() {
+            return Ok(None);
+        }
+        match (user_account, vault, token_program) {
+            (Some(user_account), Some(vault), Some(token_program)) => {
+                Ok(Some(QuoteTokenAccounts {
+                    user_account: user_account.to_account_info(),
+                    vault: vault.to_account_info(),
+                    token_program: token_program.to_account_info(),
+                }))
+            }
+            _ => err!(CustomError::MissingQuoteAccounts),
+        }
+    }
+
+    // USD price * 1000 of one whole quote token
+    pub fn quote_usd_price(&self, sol_price: u64) -> u64 {
+        if self.quote_usd_pegged {
+            1000
+        } else {
+            sol_price
+        }
+    }
+
+    // One whole launched token in base units, prices are quoted per whole token
+    pub fn token_unit(&self) -> u64 {
+        10u64.pow(self.decimals as u32)
+    }
+
+    pub fn dutch_active(&self, slot: u64) -> bool {
+        self.launch_mode == LAUNCH_MODE_DUTCH_AUCTION
+            && !self.auction_settled
+            && slot < self.dutch_end_slot
+            && self.auction_sold < self.auction_supply
+    }
+
+    // Once a dutch auction sells out or reaches its end slot, its proceeds and sold tokens move
+    // into the curve the way settle_auction does for batch auctions, so auction buyers can sell
+    // back into a reserve they funded. Until then they stay out of the curve price and the TWAP.
+    pub fn end_dutch_auction(&mut self, slot: u64) -> Result<()> {
+        if self.launch_mode == LAUNCH_MODE_DUTCH_AUCTION
+            && !self.auction_settled
+            && !self.dutch_active(slot)
+        {
+            self.sol_reserve += self.auction_raised;
+            self.token_reserve -= self.auction_sold;
+            self.auction_settled = true;
+            self.last_price = self.spot_price()?;
+        }
+        Ok(())
+    }
+
+    // Descending price of a dutch auction launch, never below the floor
+    pub fn dutch_price(&self, slot: u64) -> u64 {
+        let elapsed = slot.saturating_sub(self.dutch_start_slot);
+        let price = if self.dutch_decay == DUTCH_DECAY_EXPONENTIAL {
+            // start_price * (1 - decay_rate)^elapsed in 1e12 fixed point
+            let one: u128 = 1_000_000_000_000;
+            let mut factor = one - self.dutch_decay_rate as u128 * one / 10000;
+            let mut result = one;
+            let mut n = elapsed;
+            while n > 0 && result > 0 {
+                if n & 1 == 1 {
+                    result = result * factor / one;
+                }
+                factor = factor * factor / one;
+                n >>= 1;
+            }
+            (self.dutch_start_price as u128 * result / one) as u64
+        } else {
+            self.dutch_start_price
+                .saturating_sub(elapsed.saturating_mul(self.dutch_decay_rate))
+        };
+        price.max(self.dutch_floor_price)
+    }
+}
+
+#[account]
+pub struct SolEscrow {
+    pub mint: Pubkey,
+    pub rent_reserve: u64, // lamports funded at creation, never paid out
+}
+
+#[account]
+pub struct BuyerInfo {
+    pub sol_amount: u64,
+    pub token_amount: u64,
+}
+
+// Ring buffer of the most recent 1 minute candles, prices are per whole token and
+// volume is in quote base units
+#[account(zero_copy)]
+pub struct CandleBuffer {
+    pub mint: Pubkey,
+    pub head: u64,  // index of the latest candle
+    pub count: u64, // filled candles, up to CANDLE_COUNT
+    pub candles: [Candle; CANDLE_COUNT],
+}
+
+#[zero_copy]
+pub struct Candle {
+    pub start_time: i64,
+    pub open: u64,
+    pub high: u64,
+    pub low: u64,
+    pub close: u64,
+    pub volume: u64, // quote into or out of the curve, fees excluded on both sides
+}
+
+impl CandleBuffer {
+    pub fn record(&mut self, now: i64, price: u64, volume: u64) {
+        let start_time = now - now.rem_euclid(CANDLE_INTERVAL);
+        let latest = &mut self.candles[self.head as usize];
+        if self.count > 0 && latest.start_time == start_time {
+            latest.high = latest.high.max(price);
+            latest.low = latest.low.min(price);
+            latest.close = price;
+            latest.volume += volume;
+            return;
+        }
+        if self.count > 0 {
+            self.head = (self.head + 1) % CANDLE_COUNT as u64;
+        }
+        self.count = (self.count + 1).min(CANDLE_COUNT as u64);
+        self.candles[self.head as usize] = Candle {
+            start_time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+        };
+    }
+}
+
+// Opt-in per wallet trade history, cost basis uses the average cost of the tracked balance
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub tokens_bought: u64,
+    pub tokens_sold: u64,
+    pub sol_spent: u64,
+    pub sol_received: u64,
+    pub trade_count: u64,
+    pub token_balance: u64, // received from the pool and not sold yet
+    pub cost_basis: u64,    // quote spent on token_balance
+    pub realized_pnl: i64,
+}
+
+impl Position {
+    // Positions are created on first use, so every path that touches one sets its keys
+    pub fn bind(&mut self, owner: Pubkey, mint: Pubkey) {
+        self.owner = owner;
+        self.mint = mint;
+    }
+
+    pub fn record_buy(&mut self, sol_spent: u64, token_amount: u64) {
+        self.record_claim(sol_spent, token_amount);
+        self.trade_count += 1;
+    }
+
+    // Tokens paid out by the pool outside a trade: auction, airdrop and vesting claims
+    pub fn record_claim(&mut self, sol_spent: u64, token_amount: u64) {
+        self.tokens_bought += token_amount;
+        self.sol_spent += sol_spent;
+        self.token_balance += token_amount;
+        self.cost_basis += sol_spent;
+    }
+
+    pub fn record_sell(&mut self, sol_received: u64, token_amount: u64) -> Result<()> {
+        // Tokens that came from outside the curve carry no cost
+        let tracked = token_amount.min(self.token_balance);
+        let cost = if self.token_balance > 0 {
+            mul_div(self.cost_basis, tracked, self.token_balance)?
+        } else {
+            0
+        };
+        self.tokens_sold += token_amount;
+        self.sol_received += sol_received;
+        self.token_balance -= tracked;
+        self.cost_basis -= cost;
+        self.realized_pnl += sol_received as i64 - cost as i64;
+        self.trade_count += 1;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct AuctionCommitment {
+    pub sol_amount: u64,
+    pub claimed: bool,
+}
+
+#[account]
+pub struct LaunchRecord {
+    pub index: u32,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub created_at: i64,
+    pub status: u8, // 0 -> active, 1 -> graduated, 2 -> cancelled
+}
+
+#[account]
+pub struct CreatorInfo {
+    pub creator: Pubkey,
+    pub launch_count: u32,
+    pub graduated_count: u32,
+    pub cancelled_count: u32,
+    pub mints: Vec<Pubkey>, // in launch order, the account grows with every launch
+}
+
+#[account]
+pub struct LaunchProfile {
+    pub mint: Pubkey,
+    pub description: String,
+    pub website: String,
+    pub twitter: String,
+    pub telegram: String,
+    pub image_hash: [u8; 32],
+    pub updated_at: i64,
+}
+
+#[account]
+pub struct CreatorVesting {
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_at: i64,
+    pub cliff_at: i64,
+    pub end_at: i64,
+}
+
+impl CreatorVesting {
+    // Linear release from start_at to end_at, nothing before the cliff
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if now < self.cliff_at {
+            Ok(0)
+        } else if now >= self.end_at {
+            Ok(self.total_amount)
+        } else {
+            mul_div(
+                self.total_amount,
+                (now - self.start_at) as u64,
+                (self.end_at - self.start_at) as u64,
+            )
+        }
+    }
+}
+
+#[account]
+pub struct Airdrop {
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub expires_at: i64,
+    pub claimed_bitmap: Vec<u8>, // one bit per leaf index
+}
+
+#[account]
+pub struct NameRecord {
+    pub owner: Pubkey,
+    pub name_hash: [u8; 32],
+    pub mint: Pubkey,    // Pubkey::default() -> reserved but not launched yet
+    pub expires_at: i64, // reservations lapse here, launched names never do
 }
 
 #[error_code]
@@ -627,4 +4086,104 @@ pub enum CustomError {
     InvalidTokenAmount,
     #[msg("You are not a owner.")]
     NotOwner,
+    #[msg("Trading has not started yet.")]
+    TradingNotStarted,
+    #[msg("Buy amount exceeds the per-wallet limit.")]
+    BuyLimitExceeded,
+    #[msg("You are not the creator.")]
+    NotCreator,
+    #[msg("Trading has already started.")]
+    TradingAlreadyStarted,
+    #[msg("Allowlist phase must end after trading starts.")]
+    InvalidAllowlistEnd,
+    #[msg("Wallet is not on the allowlist.")]
+    NotAllowlisted,
+    #[msg("Initial buy is not allowed in auction mode.")]
+    InitialBuyNotAllowed,
+    #[msg("Invalid auction parameters.")]
+    InvalidAuctionParams,
+    #[msg("Not supported in this launch mode.")]
+    InvalidLaunchMode,
+    #[msg("Auction has ended.")]
+    AuctionEnded,
+    #[msg("Auction has not ended yet.")]
+    AuctionNotEnded,
+    #[msg("Auction is already settled.")]
+    AuctionAlreadySettled,
+    #[msg("Auction is not settled yet.")]
+    AuctionNotSettled,
+    #[msg("Already claimed.")]
+    AlreadyClaimed,
+    #[msg("Nothing to claim.")]
+    NothingToClaim,
+    #[msg("Quote mint is not allowed.")]
+    QuoteMintNotAllowed,
+    #[msg("Quote mint list is full.")]
+    QuoteMintListFull,
+    #[msg("Quote token accounts are missing.")]
+    MissingQuoteAccounts,
+    #[msg("Metaplex metadata accounts are missing.")]
+    MissingMetadataAccounts,
+    #[msg("Transfer fees require a Token-2022 launch.")]
+    TransferFeeRequiresToken2022,
+    #[msg("Total supply overflows for these decimals.")]
+    SupplyOverflow,
+    #[msg("Name is reserved by another creator.")]
+    NameReserved,
+    #[msg("Name is already used by another launch.")]
+    NameAlreadyUsed,
+    #[msg("Launch was cancelled.")]
+    LaunchCancelled,
+    #[msg("Platform collection accounts are missing.")]
+    MissingCollectionAccounts,
+    #[msg("Launch has already graduated.")]
+    LaunchGraduated,
+    #[msg("Profile field is too long.")]
+    ProfileFieldTooLong,
+    #[msg("Invalid vesting parameters.")]
+    InvalidVestingParams,
+    #[msg("Vesting account is missing.")]
+    MissingVestingAccount,
+    #[msg("Invalid airdrop parameters.")]
+    InvalidAirdropParams,
+    #[msg("Airdrop account is missing.")]
+    MissingAirdropAccount,
+    #[msg("Invalid airdrop proof.")]
+    InvalidAirdropProof,
+    #[msg("Airdrop has expired.")]
+    AirdropExpired,
+    #[msg("Airdrop has not expired yet.")]
+    AirdropNotExpired,
+    #[msg("TWAP window has not elapsed yet.")]
+    TwapWindowNotElapsed,
+    #[msg("Market cap target is not reached.")]
+    MarketCapNotReached,
+    #[msg("Position account is required for this pool.")]
+    MissingPosition,
+    #[msg("Not enough holders to graduate.")]
+    NotEnoughHolders,
+    #[msg("Largest holder owns too much of the supply.")]
+    HolderConcentrationTooHigh,
+    #[msg("Position does not belong to this pool.")]
+    InvalidPosition,
+    #[msg("Math overflow.")]
+    MathOverflow,
+    #[msg("Start price rounds to zero.")]
+    ZeroStartPrice,
+    #[msg("Pool is paused.")]
+    PoolPaused,
+    #[msg("Position is passed more than once.")]
+    DuplicatePosition,
+    #[msg("Account is already migrated.")]
+    AlreadyMigrated,
+    #[msg("Global info must be migrated first.")]
+    NotMigrated,
+    #[msg("Launch index is not free.")]
+    InvalidLaunchIndex,
+    #[msg("Name was reserved by this wallet too recently.")]
+    NameReservationCooldown,
+    #[msg("Launches with an initial buy cannot be cancelled.")]
+    CancelAfterInitialBuy,
+    #[msg("Value must not exceed 10000 basis points.")]
+    InvalidBasisPoints,
 }
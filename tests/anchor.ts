@@ -2,6 +2,7 @@ import BN from "bn.js";
 import assert from "assert";
 import * as web3 from "@solana/web3.js";
 import * as anchor from "@coral-xyz/anchor";
+import { keccak_256 } from "@noble/hashes/sha3";
 import type { TokenMinter } from "../target/types/token_minter";
 describe("Test Minter", () => {
   // Configure the client to use the local cluster
@@ -14,14 +15,35 @@ describe("Test Minter", () => {
   const TOKEN_METADATA_PROGRAM_ID = new web3.PublicKey(
     "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
   );
+  const TOKEN_2022_PROGRAM_ID = new web3.PublicKey(
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+  );
 
   // Constants from our program
   const MINT_SEED = "mint";
   const GLOBAL_INFO_SEED = "global_info";
   const TOKEN_POOL_SEED = "token_pool";
   const SOL_VAULT_SEED = "sol_escrow_seed";
+  const BUYER_INFO_SEED = "buyer_info";
+  const LAUNCH_SEED = "launch";
+  const CREATOR_INFO_SEED = "creator_info";
+  const CANDLES_SEED = "candles";
+  const NAME_RECORD_SEED = "name_record";
+  const POSITION_SEED = "position";
+  const LAUNCH_PROFILE_SEED = "launch_profile";
+  const VESTING_SEED = "vesting";
+  const VESTING_VAULT_SEED = "vesting_vault";
+  const AIRDROP_SEED = "airdrop";
+  const AIRDROP_VAULT_SEED = "airdrop_vault";
+  const AUCTION_COMMITMENT_SEED = "auction_commitment";
+  const TRANSFER_FEE_AUTHORITY_SEED = "transfer_fee_authority";
+  const TRANSFER_FEE_VAULT_SEED = "transfer_fee_vault";
+  const QUOTE_VAULT_SEED = "quote_vault";
 
   const tokenName = "great123"
+  // Mints are derived from the launch index, fresh validators start at 0
+  const launchIndex = 0;
+  const launchSeed = (index: number) => new BN(index).toArrayLike(Buffer, "le", 4);
   // Data for our tests
   const payer = program.provider.publicKey;
   const metadata = {
@@ -29,10 +51,17 @@ describe("Test Minter", () => {
     symbol: "TEST",
     uri: "https://5vfxc4tr6xoy23qefqbj4qx2adzkzapneebanhcalf7myvn5gzja.arweave.net/7UtxcnH13Y1uBCwCnkL6APKsge0hAgacQFl-zFW9NlI",
     decimals: 9,
+    tradingStartsAt: new BN(0),
+    batchAuction: null,
+    dutchAuction: null,
+    transferFee: null,
+    vesting: null,
+    airdrop: null,
+    allowlist: null,
   };
   const mintAmount = 0.1;
   const [mint] = web3.PublicKey.findProgramAddressSync(
-    [Buffer.from(MINT_SEED), Buffer.from(tokenName)],
+    [Buffer.from(MINT_SEED), launchSeed(launchIndex)],
     program.programId
   );
 
@@ -56,6 +85,303 @@ describe("Test Minter", () => {
     program.programId
   );
 
+  const [launch] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(LAUNCH_SEED), launchSeed(launchIndex)],
+    program.programId
+  );
+
+  const [creatorInfo] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(CREATOR_INFO_SEED), payer.toBuffer()],
+    program.programId
+  );
+
+  // Reserved names live in the registry, keyed by the lowercased name's keccak hash
+  const nameRecordFor = (name: string) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(NAME_RECORD_SEED), Buffer.from(keccak_256(name.toLowerCase()))],
+      program.programId
+    )[0];
+
+  const [candles] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(CANDLES_SEED), mint.toBuffer()],
+    program.programId
+  );
+
+  const [buyerInfo] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(BUYER_INFO_SEED), mint.toBuffer(), payer.toBuffer()],
+    program.programId
+  );
+
+  const [launchProfile] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(LAUNCH_PROFILE_SEED), mint.toBuffer()],
+    program.programId
+  );
+
+  // Awaits an instruction the program must reject with the given error
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+    } catch (e) {
+      assert.strictEqual(e.error?.errorCode?.code, code, `  Expected ${code}, got ${e}`);
+      return;
+    }
+    assert.fail(`  Expected ${code}.`);
+  };
+
+  const pda = (seeds: Buffer[]) =>
+    web3.PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const associatedAddress2022 = (owner: web3.PublicKey, tokenMint: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [owner.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), tokenMint.toBuffer()],
+      anchor.utils.token.ASSOCIATED_PROGRAM_ID
+    )[0];
+  const tokenBalance = async (account: web3.PublicKey) =>
+    new BN((await program.provider.connection.getTokenAccountBalance(account)).value.amount);
+  const sleep = (seconds: number) => new Promise(resolve => setTimeout(resolve, seconds * 1000));
+  const now = () => Math.floor(Date.now() / 1000);
+
+  // Sorted-pair keccak merkle node, the order the program verifies proofs in
+  const hashPair = (a: Buffer, b: Buffer) =>
+    Buffer.from(keccak_256(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])));
+  const u32 = (value: number) => new BN(value).toArrayLike(Buffer, "le", 4);
+  const u64 = (value: BN) => value.toArrayLike(Buffer, "le", 8);
+
+  // Classic SPL mint held by the payer, with the payer's associated account funded, built from
+  // raw instructions since the tests do not pull in @solana/spl-token
+  const createQuoteMint = async (decimals: number, supply: BN) => {
+    const connection = program.provider.connection;
+    const quoteMint = web3.Keypair.generate();
+    const payerAccount = anchor.utils.token.associatedAddress({ mint: quoteMint.publicKey, owner: payer });
+    const tokenProgramId = anchor.utils.token.TOKEN_PROGRAM_ID;
+    await program.provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.createAccount({
+          fromPubkey: payer,
+          newAccountPubkey: quoteMint.publicKey,
+          lamports: await connection.getMinimumBalanceForRentExemption(82),
+          space: 82,
+          programId: tokenProgramId,
+        }),
+        // InitializeMint2 without a freeze authority
+        new web3.TransactionInstruction({
+          programId: tokenProgramId,
+          keys: [{ pubkey: quoteMint.publicKey, isSigner: false, isWritable: true }],
+          data: Buffer.concat([Buffer.from([20, decimals]), payer.toBuffer(), Buffer.from([0])]),
+        }),
+        new web3.TransactionInstruction({
+          programId: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          keys: [
+            { pubkey: payer, isSigner: true, isWritable: true },
+            { pubkey: payerAccount, isSigner: false, isWritable: true },
+            { pubkey: payer, isSigner: false, isWritable: false },
+            { pubkey: quoteMint.publicKey, isSigner: false, isWritable: false },
+            { pubkey: web3.SystemProgram.programId, isSigner: false, isWritable: false },
+            { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+          ],
+          data: Buffer.alloc(0),
+        }),
+        // MintTo
+        new web3.TransactionInstruction({
+          programId: tokenProgramId,
+          keys: [
+            { pubkey: quoteMint.publicKey, isSigner: false, isWritable: true },
+            { pubkey: payerAccount, isSigner: false, isWritable: true },
+            { pubkey: payer, isSigner: true, isWritable: false },
+          ],
+          data: Buffer.concat([Buffer.from([7]), u64(supply)]),
+        })
+      ),
+      [quoteMint]
+    );
+    return { mint: quoteMint.publicKey, payerAccount };
+  };
+
+  // Creates the next Token-2022 launch with the given InitTokenParams overrides, holder gated
+  // launches need the creator's position for the initial buy
+  const createLaunch = async (
+    name: string,
+    params: Record<string, unknown>,
+    amount: BN,
+    {
+      creator,
+      nameRecord = null,
+      quote = null,
+      withPosition = false,
+    }: {
+      creator?: web3.Keypair;
+      nameRecord?: web3.PublicKey | null;
+      quote?: Awaited<ReturnType<typeof createQuoteMint>> | null;
+      withPosition?: boolean;
+    } = {}
+  ) => {
+    const index = (await program.account.globalInfo.fetch(globalInfo)).tokenCount;
+    const creatorKey = creator ? creator.publicKey : payer;
+    const launchMint = pda([Buffer.from(MINT_SEED), launchSeed(index)]);
+    const accounts = {
+      mint: launchMint,
+      tokenVault: pda([launchMint.toBuffer()]),
+      escrowAccount: pda([Buffer.from(SOL_VAULT_SEED), launchMint.toBuffer()]),
+      tokenPools: pda([Buffer.from(TOKEN_POOL_SEED), launchMint.toBuffer()]),
+      candles: pda([Buffer.from(CANDLES_SEED), launchMint.toBuffer()]),
+      launch: pda([Buffer.from(LAUNCH_SEED), launchSeed(index)]),
+      destination: associatedAddress2022(creatorKey, launchMint),
+    };
+    await program.methods
+      .createToken({ ...metadata, name, ...params }, amount)
+      .accounts({
+        ...accounts,
+        nameRecord,
+        metadata: null,
+        collectionMint: null,
+        collectionMetadata: null,
+        collectionMasterEdition: null,
+        collectionAuthority: null,
+        globalInfo,
+        creatorInfo: pda([Buffer.from(CREATOR_INFO_SEED), creatorKey.toBuffer()]),
+        vesting: params.vesting ? pda([Buffer.from(VESTING_SEED), launchMint.toBuffer()]) : null,
+        vestingVault: params.vesting
+          ? pda([Buffer.from(VESTING_VAULT_SEED), launchMint.toBuffer()])
+          : null,
+        airdrop: params.airdrop ? pda([Buffer.from(AIRDROP_SEED), launchMint.toBuffer()]) : null,
+        airdropVault: params.airdrop
+          ? pda([Buffer.from(AIRDROP_VAULT_SEED), launchMint.toBuffer()])
+          : null,
+        position: withPosition
+          ? pda([Buffer.from(POSITION_SEED), launchMint.toBuffer(), creatorKey.toBuffer()])
+          : null,
+        quoteMint: quote ? quote.mint : null,
+        quoteVault: quote ? pda([Buffer.from(QUOTE_VAULT_SEED), launchMint.toBuffer()]) : null,
+        payerQuoteAccount: quote ? quote.payerAccount : null,
+        payer: creatorKey,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteTokenProgram: quote ? anchor.utils.token.TOKEN_PROGRAM_ID : null,
+        tokenMetadataProgram: null,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .signers(creator ? [creator] : [])
+      .rpc();
+    return accounts;
+  };
+
+  const buyLaunch = (
+    launchAccounts: Awaited<ReturnType<typeof createLaunch>>,
+    amount: BN,
+    allowlistProof: { allocation: BN; proof: number[][] } | null = null
+  ) =>
+    program.methods
+      .buyToken(amount, allowlistProof)
+      .accounts({
+        buyer: payer,
+        mint: launchAccounts.mint,
+        tokenVault: launchAccounts.tokenVault,
+        escrowAccount: launchAccounts.escrowAccount,
+        globalInfo,
+        tokenPools: launchAccounts.tokenPools,
+        buyerTokenAccount: associatedAddress2022(payer, launchAccounts.mint),
+        buyerInfo: pda([Buffer.from(BUYER_INFO_SEED), launchAccounts.mint.toBuffer(), payer.toBuffer()]),
+        position: null,
+        candles: launchAccounts.candles,
+        quoteVault: null,
+        buyerQuoteAccount: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteTokenProgram: null,
+        systemProgram: web3.SystemProgram.programId,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .rpc();
+
+  const sellLaunch = (launchAccounts: Awaited<ReturnType<typeof createLaunch>>, amount: BN) =>
+    program.methods
+      .sellToken(amount)
+      .accounts({
+        seller: payer,
+        mint: launchAccounts.mint,
+        tokenVault: launchAccounts.tokenVault,
+        escrowAccount: launchAccounts.escrowAccount,
+        globalInfo,
+        tokenPools: launchAccounts.tokenPools,
+        sellerTokenAccount: associatedAddress2022(payer, launchAccounts.mint),
+        position: null,
+        candles: launchAccounts.candles,
+        quoteVault: null,
+        sellerQuoteAccount: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteTokenProgram: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+  // Smallest buy whose amount net of the pool fee is exactly `net`, the net amount grows by
+  // at most one per lamport so the search always lands on it
+  const grossForNet = (net: BN, feePercent: number) => {
+    const netOf = (gross: BN) => gross.sub(gross.muln(feePercent).divn(10000));
+    let gross = net.muln(10000).divn(10000 - feePercent);
+    while (netOf(gross).lt(net)) {
+      gross = gross.addn(1);
+    }
+    return gross;
+  };
+
+  const waitForSlot = async (slot: number) => {
+    while ((await program.provider.connection.getSlot()) < slot) {
+      await sleep(0.4);
+    }
+  };
+
+  // Graduates a launch at a 100 USD quote price, holder capped pools take positions as remaining accounts
+  const addLiquidityLaunch = (
+    launchAccounts: Awaited<ReturnType<typeof createLaunch>>,
+    positions: web3.PublicKey[] = []
+  ) =>
+    program.methods
+      .addLiquidity(new BN(100000000))
+      .accounts({
+        mint: launchAccounts.mint,
+        userTokenCoin: associatedAddress2022(payer, launchAccounts.mint),
+        userTokenPc: anchor.utils.token.associatedAddress({
+          mint: new web3.PublicKey("So11111111111111111111111111111111111111112"),
+          owner: payer,
+        }),
+        quoteVault: null,
+        tokenVault: launchAccounts.tokenVault,
+        escrowAccount: launchAccounts.escrowAccount,
+        payer,
+        globalInfo,
+        tokenPools: launchAccounts.tokenPools,
+        launch: launchAccounts.launch,
+        creatorInfo,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteTokenProgram: null,
+        systemProgram: web3.SystemProgram.programId,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        positions.map(pubkey => ({ pubkey, isSigner: false, isWritable: false }))
+      )
+      .rpc();
+
+  const profile = {
+    description: "Community launch for the test suite",
+    website: "https://example.com",
+    twitter: "@example",
+    telegram: "t.me/example",
+    imageHash: Array.from(keccak_256("image")),
+  };
+  const profileContext = {
+    mint,
+    tokenPools,
+    launchProfile,
+    creator: payer,
+    systemProgram: web3.SystemProgram.programId,
+  };
+
+  const [position] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(POSITION_SEED), mint.toBuffer(), payer.toBuffer()],
+    program.programId
+  );
+
   console.log("mint", mint.toBase58());
   console.log("tokenVault", tokenVault.toBase58());
   console.log("escrowAccount", escrowAccount.toBase58());
@@ -70,6 +396,23 @@ describe("Test Minter", () => {
     TOKEN_METADATA_PROGRAM_ID
   );
 
+  it("initialize", async () => {
+    const info = await program.provider.connection.getAccountInfo(globalInfo);
+    if (info) {
+      return; // initialize would reset the admin settings
+    }
+    await program.methods
+      .initialize()
+      .accounts({
+        globalInfo,
+        owner: payer,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    const global = await program.account.globalInfo.fetch(globalInfo);
+    assert(global.owner.equals(payer), "  Payer should own the global config.");
+  });
+
     // Test init token
   it("create token", async () => {
     const info = await program.provider.connection.getAccountInfo(mint);
@@ -85,17 +428,34 @@ describe("Test Minter", () => {
     });
 
     const context = {
+      nameRecord: null,
       metadata: metadataAddress,
+      collectionMint: null,
+      collectionMetadata: null,
+      collectionMasterEdition: null,
+      collectionAuthority: null,
       mint,
       destination,
       tokenVault,
       escrowAccount,
       globalInfo,
+      launch,
+      creatorInfo,
+      vesting: null,
+      vestingVault: null,
+      airdrop: null,
+      airdropVault: null,
+      candles,
+      position,
       tokenPools,
+      quoteMint: null,
+      quoteVault: null,
+      payerQuoteAccount: null,
       payer,
       rent: web3.SYSVAR_RENT_PUBKEY,
       systemProgram: web3.SystemProgram.programId,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      quoteTokenProgram: null,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     };
@@ -103,13 +463,225 @@ describe("Test Minter", () => {
     const txHash = await program.methods
       .createToken(metadata, new BN(mintAmount * 10 ** metadata.decimals))
       .accounts(context)
-      .rpc()
-      .catch(e => console.log(e));
+      .rpc();
 
     await program.provider.connection.confirmTransaction(txHash, "finalized");
     console.log(`  https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
     const newInfo = await program.provider.connection.getAccountInfo(mint);
     assert(newInfo, "  Mint should be initialized.");
+    // COption tag of the mint authority, 0 -> None
+    assert(newInfo.data.readUInt32LE(0) === 0, "  Mint authority should be revoked.");
+    const creatorPosition = await program.account.position.fetch(position);
+    assert(creatorPosition.owner.equals(payer), "  Position should belong to the creator.");
+    assert(creatorPosition.mint.equals(mint), "  Position should belong to the mint.");
+    assert(creatorPosition.tokenBalance.gtn(0), "  Position should hold the initial buy.");
+  });
+
+  it("update metadata", async () => {
+    const context = {
+      mint,
+      metadata: metadataAddress,
+      tokenPools,
+      creator: payer,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: null,
+      systemProgram: web3.SystemProgram.programId,
+    };
+
+    const txHash = await program.methods
+      .updateMetadata(metadata.name, metadata.symbol, metadata.uri)
+      .accounts(context)
+      .rpc();
+
+    await program.provider.connection.confirmTransaction(txHash, "finalized");
+    console.log(`  https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
+  });
+
+  it("create token-2022 token", async () => {
+    const name2022 = tokenName + "22";
+    const [mint2022] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(MINT_SEED), launchSeed(launchIndex + 1)],
+      program.programId
+    );
+    const info = await program.provider.connection.getAccountInfo(mint2022);
+    if (info) {
+      console.log("Already minted!!!")
+      return; // Do not attempt to initialize if already initialized
+    }
+
+    const [destination] = web3.PublicKey.findProgramAddressSync(
+      [payer.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), mint2022.toBuffer()],
+      anchor.utils.token.ASSOCIATED_PROGRAM_ID
+    );
+    const [tokenVault2022] = web3.PublicKey.findProgramAddressSync(
+      [mint2022.toBuffer()],
+      program.programId
+    );
+    const [escrowAccount2022] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(SOL_VAULT_SEED), mint2022.toBuffer()],
+      program.programId
+    );
+    const [tokenPools2022] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(TOKEN_POOL_SEED), mint2022.toBuffer()],
+      program.programId
+    );
+    const [candles2022] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(CANDLES_SEED), mint2022.toBuffer()],
+      program.programId
+    );
+    const [launch2022] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(LAUNCH_SEED), launchSeed(launchIndex + 1)],
+      program.programId
+    );
+
+    const context = {
+      nameRecord: null,
+      metadata: null,
+      collectionMint: null,
+      collectionMetadata: null,
+      collectionMasterEdition: null,
+      collectionAuthority: null,
+      mint: mint2022,
+      destination,
+      tokenVault: tokenVault2022,
+      escrowAccount: escrowAccount2022,
+      globalInfo,
+      launch: launch2022,
+      creatorInfo,
+      vesting: null,
+      vestingVault: null,
+      airdrop: null,
+      airdropVault: null,
+      candles: candles2022,
+      position: null,
+      tokenPools: tokenPools2022,
+      quoteMint: null,
+      quoteVault: null,
+      payerQuoteAccount: null,
+      payer,
+      rent: web3.SYSVAR_RENT_PUBKEY,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      quoteTokenProgram: null,
+      tokenMetadataProgram: null,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+
+    const txHash = await program.methods
+      .createToken({ ...metadata, name: name2022 }, new BN(mintAmount * 10 ** metadata.decimals))
+      .accounts(context)
+      .rpc();
+
+    await program.provider.connection.confirmTransaction(txHash, "finalized");
+    console.log(`  https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
+    const newInfo = await program.provider.connection.getAccountInfo(mint2022);
+    assert(newInfo, "  Mint should be initialized.");
+    assert(newInfo.owner.equals(TOKEN_2022_PROGRAM_ID), "  Mint should be owned by Token-2022.");
+  });
+
+  it("update token-2022 metadata", async () => {
+    const [mint2022] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(MINT_SEED), launchSeed(launchIndex + 1)],
+      program.programId
+    );
+    const [tokenPools2022] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(TOKEN_POOL_SEED), mint2022.toBuffer()],
+      program.programId
+    );
+    const uri = metadata.uri + "?v=2";
+
+    const txHash = await program.methods
+      .updateMetadata(tokenName + "22", metadata.symbol, uri)
+      .accounts({
+        mint: mint2022,
+        metadata: null,
+        tokenPools: tokenPools2022,
+        creator: payer,
+        tokenMetadataProgram: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.provider.connection.confirmTransaction(txHash, "finalized");
+    const newInfo = await program.provider.connection.getAccountInfo(mint2022);
+    assert(newInfo.data.includes(Buffer.from(uri)), "  Mint metadata should carry the new uri.");
+  });
+
+  it("launch registry", async () => {
+    for (const index of [launchIndex, launchIndex + 1]) {
+      const [launchMint] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from(MINT_SEED), launchSeed(index)],
+        program.programId
+      );
+      const [record] = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from(LAUNCH_SEED), launchSeed(index)],
+        program.programId
+      );
+      const entry = await program.account.launchRecord.fetch(record);
+      assert.strictEqual(entry.index, index, "  Registry entry should carry its index.");
+      assert(entry.mint.equals(launchMint), "  Registry entry should point at the launch mint.");
+      assert(entry.creator.equals(payer), "  Registry entry should record the creator.");
+      assert.strictEqual(entry.status, 0, "  Launch should be active.");
+    }
+    const creator = await program.account.creatorInfo.fetch(creatorInfo);
+    assert(creator.launchCount >= 2, "  Creator should count both launches.");
+  });
+
+  it("name registry", async () => {
+    const name = tokenName + "n";
+    const nameRecord = nameRecordFor(name);
+    await program.methods
+      .reserveName(name)
+      .accounts({ nameRecord, owner: payer, systemProgram: web3.SystemProgram.programId })
+      .rpc();
+    let record = await program.account.nameRecord.fetch(nameRecord);
+    assert(record.owner.equals(payer), "  Name should be reserved by the payer.");
+    assert(record.expiresAt.gtn(now()), "  Reservation should be live.");
+
+    // Names are case-insensitive, a live reservation keeps other wallets out
+    const other = web3.Keypair.generate();
+    await expectError(
+      program.methods
+        .reserveName(name.toUpperCase())
+        .accounts({ nameRecord, owner: other.publicKey, systemProgram: web3.SystemProgram.programId })
+        .signers([other])
+        .rpc(),
+      "NameReserved"
+    );
+
+    // Launches without the record share the name, the reserving creator binds it once
+    await createLaunch(name, {}, new BN(0));
+    record = await program.account.nameRecord.fetch(nameRecord);
+    assert(record.mint.equals(web3.PublicKey.default), "  Unbound launches should leave the record alone.");
+    const bound = await createLaunch(name, {}, new BN(0), { nameRecord });
+    record = await program.account.nameRecord.fetch(nameRecord);
+    assert(record.mint.equals(bound.mint), "  Name should be bound to the launch.");
+    await expectError(
+      createLaunch(name, {}, new BN(0), { nameRecord }),
+      "NameAlreadyUsed"
+    );
+  });
+
+  it("create token over prefunded mint and vault addresses", async () => {
+    // Anyone can predict the next mint and vault PDAs and send them lamports
+    const index = (await program.account.globalInfo.fetch(globalInfo)).tokenCount;
+    const nextMint = pda([Buffer.from(MINT_SEED), launchSeed(index)]);
+    const nextVault = pda([nextMint.toBuffer()]);
+    await program.provider.sendAndConfirm(
+      new web3.Transaction().add(
+        ...[nextMint, nextVault].map(toPubkey =>
+          web3.SystemProgram.transfer({ fromPubkey: payer, toPubkey, lamports: 1 })
+        )
+      )
+    );
+
+    const launchAccounts = await createLaunch(tokenName + "p", {}, new BN(0));
+    assert(launchAccounts.mint.equals(nextMint), "  Launch should take the prefunded mint.");
+    const mintInfo = await program.provider.connection.getAccountInfo(nextMint);
+    assert(mintInfo.owner.equals(TOKEN_2022_PROGRAM_ID), "  Mint should be created anyway.");
+    const vaultInfo = await program.provider.connection.getAccountInfo(nextVault);
+    assert(vaultInfo.owner.equals(TOKEN_2022_PROGRAM_ID), "  Vault should be created anyway.");
   });
 
   it("buy token", async () => {
@@ -126,16 +698,21 @@ describe("Test Minter", () => {
       globalInfo,
       tokenPools, 
       buyerTokenAccount: destination,
+      buyerInfo,
+      position: null,
+      candles,
+      quoteVault: null,
+      buyerQuoteAccount: null,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      quoteTokenProgram: null,
       systemProgram: web3.SystemProgram.programId,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     };
     console.log("destination", destination.toBase58());
     const txHash = await program.methods
-      .buyToken(new BN(0.1 * 10 ** metadata.decimals))
+      .buyToken(new BN(0.1 * 10 ** metadata.decimals), null)
       .accounts(context)
-      .rpc()
-      .catch(e => console.log(e));
+      .rpc();
 
     await program.provider.connection.confirmTransaction(txHash, "finalized");
     console.log(`  https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
@@ -155,20 +732,103 @@ describe("Test Minter", () => {
       globalInfo,
       tokenPools, 
       sellerTokenAccount: destination,
+      position: null,
+      candles,
+      quoteVault: null,
+      sellerQuoteAccount: null,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      quoteTokenProgram: null,
       systemProgram: web3.SystemProgram.programId,
     };
     console.log("destination", destination.toBase58());
     const txHash = await program.methods
       .sellToken(new BN(1000000 * 10 ** metadata.decimals))
       .accounts(context)
-      .rpc()
-      .catch(e => console.log(e));
+      .rpc();
 
     await program.provider.connection.confirmTransaction(txHash, "finalized");
     console.log(`  https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
   });
 
+  it("candles count curve volume net of fees", async () => {
+    const launchAccounts = await createLaunch(
+      tokenName + "k",
+      {},
+      new BN(0.01 * 10 ** metadata.decimals)
+    );
+    const totalVolume = async () => {
+      const buffer = await program.account.candleBuffer.fetch(launchAccounts.candles);
+      return buffer.candles
+        .slice(0, buffer.count.toNumber())
+        .reduce((sum, candle) => sum.add(candle.volume), new BN(0));
+    };
+    const reserve = async () =>
+      (await program.account.tokenPools.fetch(launchAccounts.tokenPools)).solReserve;
+
+    // Both sides count what reached or left the reserve, the fee is in neither
+    let volume = await totalVolume();
+    let before = await reserve();
+    await buyLaunch(launchAccounts, new BN(0.05 * web3.LAMPORTS_PER_SOL));
+    const bought = (await reserve()).sub(before);
+    assert((await totalVolume()).sub(volume).eq(bought), "  Buy volume should be net of the fee.");
+
+    volume = await totalVolume();
+    before = await reserve();
+    const balance = await tokenBalance(launchAccounts.destination);
+    await sellLaunch(launchAccounts, balance.divn(2));
+    const sold = before.sub(await reserve());
+    assert((await totalVolume()).sub(volume).eq(sold), "  Sell volume should be net of the fee.");
+  });
+
+  it("trading waits for trading_starts_at", async () => {
+    const launchAccounts = await createLaunch(
+      tokenName + "t",
+      { tradingStartsAt: new BN(now() + 3600) },
+      new BN(0.01 * 10 ** metadata.decimals)
+    );
+
+    // The creator's initial buy is the only trade before the start, in either direction
+    await expectError(
+      buyLaunch(launchAccounts, new BN(0.01 * web3.LAMPORTS_PER_SOL)),
+      "TradingNotStarted"
+    );
+    const balance = await tokenBalance(launchAccounts.destination);
+    assert(balance.gtn(0), "  Creator should hold the initial buy.");
+    await expectError(sellLaunch(launchAccounts, balance), "TradingNotStarted");
+  });
+
+  it("sniper window caps buys only while it lasts", async () => {
+    const adminContext = { globalInfo, admin: payer, systemProgram: web3.SystemProgram.programId };
+    const { sniperWindowSeconds, maxBuyAmount } = await program.account.globalInfo.fetch(globalInfo);
+    const window = 10;
+    await program.methods.setSniperWindowSeconds(new BN(window)).accounts(adminContext).rpc();
+    await program.methods
+      .setMaxBuyAmount(new BN(0.05 * web3.LAMPORTS_PER_SOL))
+      .accounts(adminContext)
+      .rpc();
+    const launchAccounts = await createLaunch(
+      tokenName + "s",
+      {},
+      new BN(0.01 * 10 ** metadata.decimals)
+    );
+    const startsAt = (await program.account.tokenPools.fetch(launchAccounts.tokenPools))
+      .tradingStartsAt.toNumber();
+
+    // Buys add up per wallet against the cap inside the window
+    await buyLaunch(launchAccounts, new BN(0.04 * web3.LAMPORTS_PER_SOL));
+    await expectError(
+      buyLaunch(launchAccounts, new BN(0.02 * web3.LAMPORTS_PER_SOL)),
+      "BuyLimitExceeded"
+    );
+
+    // The same buy goes through once the window is over
+    await sleep(Math.max(startsAt + window - now(), 0) + 2);
+    await buyLaunch(launchAccounts, new BN(0.02 * web3.LAMPORTS_PER_SOL));
+
+    await program.methods.setSniperWindowSeconds(sniperWindowSeconds).accounts(adminContext).rpc();
+    await program.methods.setMaxBuyAmount(maxBuyAmount).accounts(adminContext).rpc();
+  });
+
   it("withdraw", async () => {
    
     const context = {
@@ -176,19 +836,89 @@ describe("Test Minter", () => {
       escrowAccount,
       tokenPools, 
       globalInfo,
+      quoteVault: null,
+      adminQuoteAccount: null,
       admin: payer,
+      quoteTokenProgram: null,
       systemProgram: web3.SystemProgram.programId,
     };
     const txHash = await program.methods
       .withdrawBalance(new BN(0.05 * 10 ** metadata.decimals))
       .accounts(context)
-      .rpc()
-      .catch(e => console.log(e));
+      .rpc();
 
     await program.provider.connection.confirmTransaction(txHash, "finalized");
     console.log(`  https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
   });
 
+  it("audit pool", async () => {
+    const context = {
+      mint,
+      tokenPools,
+      globalInfo,
+      tokenVault,
+      escrowAccount,
+      quoteVault: null,
+    };
+    const txHash = await program.methods
+      .auditPool()
+      .accounts(context)
+      .rpc();
+
+    await program.provider.connection.confirmTransaction(txHash, "finalized");
+    const pool = await program.account.tokenPools.fetch(tokenPools);
+    assert(!pool.paused);
+  });
+
+  it("adopt global config", async () => {
+    const adminContext = {
+      globalInfo,
+      admin: payer,
+      systemProgram: web3.SystemProgram.programId,
+    };
+    const adoptContext = {
+      mint,
+      globalInfo,
+      tokenPools,
+      creator: payer,
+    };
+    const { feePercent, minHolderCount } = await program.account.tokenPools.fetch(tokenPools);
+    const globalHolderCount = (await program.account.globalInfo.fetch(globalInfo)).minHolderCount;
+
+    // Admin changes do not reach existing pools on their own
+    await program.methods.setFeePercent(feePercent + 100).accounts(adminContext).rpc();
+    await program.methods.setMinHolderCount(minHolderCount + 100).accounts(adminContext).rpc();
+    let pool = await program.account.tokenPools.fetch(tokenPools);
+    assert.strictEqual(pool.feePercent, feePercent, "  Pool should keep its snapshot.");
+
+    // Fees are adopted, the graduation gates of a live pool are not
+    await program.methods.adoptGlobalConfig().accounts(adoptContext).rpc();
+    pool = await program.account.tokenPools.fetch(tokenPools);
+    assert.strictEqual(pool.feePercent, feePercent + 100, "  Pool should adopt the new fee.");
+    assert.strictEqual(pool.minHolderCount, minHolderCount, "  Pool should keep its gates.");
+
+    await program.methods.setFeePercent(feePercent).accounts(adminContext).rpc();
+    await program.methods.setMinHolderCount(globalHolderCount).accounts(adminContext).rpc();
+    await program.methods.adoptGlobalConfig().accounts(adoptContext).rpc();
+  });
+
+  it("set launch profile", async () => {
+    await program.methods.setLaunchProfile(profile).accounts(profileContext).rpc();
+    let stored = await program.account.launchProfile.fetch(launchProfile);
+    assert(stored.mint.equals(mint), "  Profile should belong to the mint.");
+    assert.strictEqual(stored.description, profile.description);
+    assert.deepStrictEqual(stored.imageHash, profile.imageHash);
+
+    // Editable until graduation
+    const website = "https://example.org";
+    await program.methods
+      .setLaunchProfile({ ...profile, website })
+      .accounts(profileContext)
+      .rpc();
+    stored = await program.account.launchProfile.fetch(launchProfile);
+    assert.strictEqual(stored.website, website, "  Profile should take the new website.");
+  });
+
   it("add liquidity", async () => {
     const userTokenCoin = await anchor.utils.token.associatedAddress({
       mint: mint,
@@ -202,22 +932,767 @@ describe("Test Minter", () => {
       mint,
       userTokenCoin,
       userTokenPc,
+      quoteVault: null,
       tokenVault,
       escrowAccount,
       payer,
       globalInfo,
       tokenPools, 
+      launch,
+      creatorInfo,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      quoteTokenProgram: null,
       systemProgram: web3.SystemProgram.programId,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     };
     const txHash = await program.methods
       .addLiquidity(new BN(100000000))
       .accounts(context)
-      .rpc()
-      .catch(e => console.log(e));
+      .rpc();
 
     await program.provider.connection.confirmTransaction(txHash, "finalized");
     console.log(`  https://explorer.solana.com/tx/${txHash}?cluster=devnet`);
   });
+
+  it("launch profile is frozen after graduation", async () => {
+    await expectError(
+      program.methods.setLaunchProfile(profile).accounts(profileContext).rpc(),
+      "LaunchGraduated"
+    );
+  });
+
+  it("claim vested creator allocation", async () => {
+    const vestingAmount = new BN(1_000_000).mul(new BN(10 ** metadata.decimals));
+    const launchAccounts = await createLaunch(
+      tokenName + "v",
+      { vesting: { amount: vestingAmount, cliffSeconds: new BN(0), durationSeconds: new BN(6) } },
+      new BN(0.01 * 10 ** metadata.decimals)
+    );
+    const vesting = pda([Buffer.from(VESTING_SEED), launchAccounts.mint.toBuffer()]);
+    const vestingVault = pda([Buffer.from(VESTING_VAULT_SEED), launchAccounts.mint.toBuffer()]);
+    assert(
+      (await tokenBalance(vestingVault)).eq(vestingAmount),
+      "  Allocation should be held apart from the curve vault."
+    );
+    const claimContext = {
+      beneficiary: payer,
+      mint: launchAccounts.mint,
+      tokenPools: launchAccounts.tokenPools,
+      vesting,
+      vestingVault,
+      beneficiaryTokenAccount: launchAccounts.destination,
+      position: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+
+    // Part of the allocation is released linearly before the end
+    await sleep(2);
+    const before = await tokenBalance(launchAccounts.destination);
+    await program.methods.claimVested().accounts(claimContext).rpc();
+    let state = await program.account.creatorVesting.fetch(vesting);
+    assert(state.claimedAmount.gtn(0), "  Some tokens should have vested.");
+    assert(state.claimedAmount.lt(vestingAmount), "  Not everything should have vested yet.");
+
+    // Everything once the duration is over, and nothing after that
+    await sleep(6);
+    await program.methods.claimVested().accounts(claimContext).rpc();
+    state = await program.account.creatorVesting.fetch(vesting);
+    assert(state.claimedAmount.eq(vestingAmount), "  The whole allocation should have vested.");
+    const after = await tokenBalance(launchAccounts.destination);
+    assert(after.sub(before).eq(vestingAmount), "  Beneficiary should receive the allocation.");
+    await expectError(
+      program.methods.claimVested().accounts(claimContext).rpc(),
+      "NothingToClaim"
+    );
+    assert((await tokenBalance(vestingVault)).eqn(0), "  Vesting vault should be empty.");
+  });
+
+  it("claim airdrop", async () => {
+    const unit = new BN(10 ** metadata.decimals);
+    const recipients = [
+      { wallet: payer, amount: new BN(1_000_000).mul(unit) },
+      { wallet: web3.Keypair.generate().publicKey, amount: new BN(2_000_000).mul(unit) },
+    ];
+    // Leaves are keccak(index || wallet || amount)
+    const leaves = recipients.map(({ wallet, amount }, index) =>
+      Buffer.from(keccak_256(Buffer.concat([u32(index), wallet.toBuffer(), u64(amount)])))
+    );
+    const total = recipients[0].amount.add(recipients[1].amount);
+    const launchAccounts = await createLaunch(
+      tokenName + "a",
+      {
+        airdrop: {
+          amount: total,
+          merkleRoot: Array.from(hashPair(leaves[0], leaves[1])),
+          leafCount: leaves.length,
+          expiresAt: new BN(now() + 3600),
+        },
+      },
+      new BN(0.01 * 10 ** metadata.decimals)
+    );
+    const airdrop = pda([Buffer.from(AIRDROP_SEED), launchAccounts.mint.toBuffer()]);
+    const airdropVault = pda([Buffer.from(AIRDROP_VAULT_SEED), launchAccounts.mint.toBuffer()]);
+    assert(
+      (await tokenBalance(airdropVault)).eq(total),
+      "  Airdrop should be held apart from the curve vault."
+    );
+    const claimContext = {
+      claimer: payer,
+      mint: launchAccounts.mint,
+      tokenPools: launchAccounts.tokenPools,
+      airdrop,
+      airdropVault,
+      claimerTokenAccount: launchAccounts.destination,
+      position: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+    const claim = (index: number, amount: BN, proof: Buffer[]) =>
+      program.methods
+        .claimAirdrop(index, amount, proof.map(node => Array.from(node)))
+        .accounts(claimContext)
+        .rpc();
+
+    // Another wallet's leaf does not verify for the payer
+    await expectError(claim(1, recipients[1].amount, [leaves[0]]), "InvalidAirdropProof");
+
+    const before = await tokenBalance(launchAccounts.destination);
+    await claim(0, recipients[0].amount, [leaves[1]]);
+    const after = await tokenBalance(launchAccounts.destination);
+    assert(after.sub(before).eq(recipients[0].amount), "  Claimer should receive the leaf amount.");
+    const state = await program.account.airdrop.fetch(airdrop);
+    assert(state.claimedAmount.eq(recipients[0].amount), "  Airdrop should count the claim.");
+
+    // Each leaf pays out once
+    await expectError(claim(0, recipients[0].amount, [leaves[1]]), "AlreadyClaimed");
+    assert(
+      (await tokenBalance(airdropVault)).eq(recipients[1].amount),
+      "  Unclaimed leaves should stay in the airdrop vault."
+    );
+  });
+
+  it("cancel launch", async () => {
+    const cancel = (launchAccounts: Awaited<ReturnType<typeof createLaunch>>) =>
+      program.methods
+        .cancelLaunch()
+        .accounts({
+          mint: launchAccounts.mint,
+          tokenPools: launchAccounts.tokenPools,
+          launch: launchAccounts.launch,
+          creatorInfo,
+          creator: payer,
+        })
+        .rpc();
+    const tradingStartsAt = new BN(now() + 3600);
+
+    // The initial buy could never leave a cancelled pool
+    const withBuy = await createLaunch(
+      tokenName + "c",
+      { tradingStartsAt },
+      new BN(0.01 * web3.LAMPORTS_PER_SOL)
+    );
+    await expectError(cancel(withBuy), "CancelAfterInitialBuy");
+
+    const vestingAmount = new BN(1_000_000).mul(new BN(10 ** metadata.decimals));
+    const launchAccounts = await createLaunch(
+      tokenName + "x",
+      {
+        tradingStartsAt,
+        vesting: { amount: vestingAmount, cliffSeconds: new BN(0), durationSeconds: new BN(1) },
+      },
+      new BN(0)
+    );
+    await cancel(launchAccounts);
+    const record = await program.account.launchRecord.fetch(launchAccounts.launch);
+    assert.strictEqual(record.status, 2, "  Launch should be cancelled.");
+
+    // Allocations of a dead launch are not paid out
+    await sleep(2);
+    await expectError(
+      program.methods
+        .claimVested()
+        .accounts({
+          beneficiary: payer,
+          mint: launchAccounts.mint,
+          tokenPools: launchAccounts.tokenPools,
+          vesting: pda([Buffer.from(VESTING_SEED), launchAccounts.mint.toBuffer()]),
+          vestingVault: pda([Buffer.from(VESTING_VAULT_SEED), launchAccounts.mint.toBuffer()]),
+          beneficiaryTokenAccount: launchAccounts.destination,
+          position: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: web3.SystemProgram.programId,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .rpc(),
+      "LaunchCancelled"
+    );
+  });
+
+  it("graduation waits for the TWAP market cap", async () => {
+    const adminContext = { globalInfo, admin: payer, systemProgram: web3.SystemProgram.programId };
+    const { graduationTwapWindow, targetMarketCap } = await program.account.globalInfo.fetch(globalInfo);
+    const window = 3;
+    await program.methods.setGraduationTwapWindow(new BN(window)).accounts(adminContext).rpc();
+    await program.methods
+      .setTargetMarketCap(new BN("18446744073709551615"))
+      .accounts(adminContext)
+      .rpc();
+    const launchAccounts = await createLaunch(
+      tokenName + "w",
+      {},
+      new BN(0.01 * 10 ** metadata.decimals)
+    );
+    await program.methods.setGraduationTwapWindow(graduationTwapWindow).accounts(adminContext).rpc();
+    await program.methods.setTargetMarketCap(targetMarketCap).accounts(adminContext).rpc();
+    const pool = () => program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    const checkpoint = () =>
+      program.methods
+        .checkpointTwap()
+        .accounts({ mint: launchAccounts.mint, tokenPools: launchAccounts.tokenPools })
+        .rpc();
+
+    // No checkpoint is a window old yet, so there is no TWAP to graduate on
+    await expectError(checkpoint(), "TwapWindowNotElapsed");
+    await expectError(addLiquidityLaunch(launchAccounts), "TwapWindowNotElapsed");
+
+    // The accumulator adds the last price for every second it was held
+    await buyLaunch(launchAccounts, new BN(0.01 * web3.LAMPORTS_PER_SOL));
+    const before = await pool();
+    await sleep(2);
+    await buyLaunch(launchAccounts, new BN(0.01 * web3.LAMPORTS_PER_SOL));
+    const after = await pool();
+    const held = after.priceUpdatedAt.sub(before.priceUpdatedAt);
+    assert(held.gtn(0), "  Price should be held for some time.");
+    assert(
+      after.priceCumulative.sub(before.priceCumulative).eq(before.lastPrice.mul(held)),
+      "  Accumulator should add last price * seconds held."
+    );
+
+    // Checkpoints roll forward at most once per window
+    await sleep(window + 1);
+    const rolled = await pool();
+    await checkpoint();
+    let state = await pool();
+    assert(
+      state.prevTwapCheckpointAt.eq(rolled.twapCheckpointAt),
+      "  Previous checkpoint should take the old newest one."
+    );
+    assert(state.twapCheckpointAt.gt(rolled.twapCheckpointAt), "  Newest checkpoint should move.");
+    await expectError(checkpoint(), "TwapWindowNotElapsed");
+
+    // With a window of history the TWAP is priced, and falls short of the target
+    await expectError(addLiquidityLaunch(launchAccounts), "MarketCapNotReached");
+    state = await pool();
+    assert.strictEqual(state.launched, 0, "  Pool should not graduate.");
+  });
+
+  it("holder gates count positions and bound the largest holder", async () => {
+    const adminContext = { globalInfo, admin: payer, systemProgram: web3.SystemProgram.programId };
+    const { minHolderCount, maxHolderPercent } = await program.account.globalInfo.fetch(globalInfo);
+    await program.methods.setMinHolderCount(2).accounts(adminContext).rpc();
+    await program.methods.setMaxHolderPercent(6000).accounts(adminContext).rpc();
+    const amount = new BN(0.07 * web3.LAMPORTS_PER_SOL);
+
+    // Every payout of a gated pool goes through a position, the initial buy included
+    await expectError(createLaunch(tokenName + "h", {}, amount), "MissingPosition");
+    const launchAccounts = await createLaunch(tokenName + "h", {}, amount, { withPosition: true });
+    await program.methods.setMinHolderCount(minHolderCount).accounts(adminContext).rpc();
+    await program.methods.setMaxHolderPercent(maxHolderPercent).accounts(adminContext).rpc();
+    const positionOf = (owner: web3.PublicKey) =>
+      pda([Buffer.from(POSITION_SEED), launchAccounts.mint.toBuffer(), owner.toBuffer()]);
+    const pool = () => program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    const creatorPosition = await program.account.position.fetch(positionOf(payer));
+    let state = await pool();
+    assert.strictEqual(state.holderCount, 1, "  Creator should be the only holder.");
+    assert(state.holderSupply.eq(creatorPosition.tokenBalance), "  Holder supply should match.");
+    await expectError(buyLaunch(launchAccounts, amount), "MissingPosition");
+    await expectError(addLiquidityLaunch(launchAccounts), "NotEnoughHolders");
+
+    // A second wallet buys through its own position
+    const other = web3.Keypair.generate();
+    await program.provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: payer,
+          toPubkey: other.publicKey,
+          lamports: web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    await program.methods
+      .buyToken(amount, null)
+      .accounts({
+        buyer: other.publicKey,
+        mint: launchAccounts.mint,
+        tokenVault: launchAccounts.tokenVault,
+        escrowAccount: launchAccounts.escrowAccount,
+        globalInfo,
+        tokenPools: launchAccounts.tokenPools,
+        buyerTokenAccount: associatedAddress2022(other.publicKey, launchAccounts.mint),
+        buyerInfo: pda([
+          Buffer.from(BUYER_INFO_SEED),
+          launchAccounts.mint.toBuffer(),
+          other.publicKey.toBuffer(),
+        ]),
+        position: positionOf(other.publicKey),
+        candles: launchAccounts.candles,
+        quoteVault: null,
+        buyerQuoteAccount: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteTokenProgram: null,
+        systemProgram: web3.SystemProgram.programId,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([other])
+      .rpc();
+    const otherPosition = await program.account.position.fetch(positionOf(other.publicKey));
+    state = await pool();
+    assert.strictEqual(state.holderCount, 2, "  Both wallets should count as holders.");
+    assert(
+      state.holderSupply.eq(creatorPosition.tokenBalance.add(otherPosition.tokenBalance)),
+      "  Holder supply should add up both positions."
+    );
+
+    // Whatever is not passed in counts as one holder, so hiding the creator does not help
+    await expectError(addLiquidityLaunch(launchAccounts), "HolderConcentrationTooHigh");
+    await expectError(
+      addLiquidityLaunch(launchAccounts, [positionOf(other.publicKey), positionOf(other.publicKey)]),
+      "DuplicatePosition"
+    );
+    await expectError(addLiquidityLaunch(launchAccounts, [position]), "InvalidPosition");
+
+    // Neither wallet holds more than 60%, the one position left out is bounded by the rest
+    await addLiquidityLaunch(launchAccounts, [positionOf(other.publicKey)]);
+    assert.strictEqual((await pool()).launched, 1, "  Pool should graduate.");
+  });
+
+  it("payouts leave the escrow's rent reserve alone", async () => {
+    const launchAccounts = await createLaunch(
+      tokenName + "r",
+      {},
+      new BN(0.01 * 10 ** metadata.decimals)
+    );
+    await buyLaunch(launchAccounts, new BN(0.02 * web3.LAMPORTS_PER_SOL));
+    const { rentReserve } = await program.account.solEscrow.fetch(launchAccounts.escrowAccount);
+    const escrowLamports = () => program.provider.connection.getBalance(launchAccounts.escrowAccount);
+    const withdraw = (amount: BN) =>
+      program.methods
+        .withdrawBalance(amount)
+        .accounts({
+          mint: launchAccounts.mint,
+          escrowAccount: launchAccounts.escrowAccount,
+          tokenPools: launchAccounts.tokenPools,
+          globalInfo,
+          quoteVault: null,
+          adminQuoteAccount: null,
+          admin: payer,
+          quoteTokenProgram: null,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    // Graduation needs more than the deposits, the rent reserve does not make up the difference
+    await expectError(addLiquidityLaunch(launchAccounts), "InvalidSolAmount");
+
+    // Withdrawals stop at the reserve
+    const available = new BN(await escrowLamports()).sub(rentReserve);
+    await expectError(withdraw(available.addn(1)), "InvalidSolAmount");
+    await withdraw(available);
+    assert.strictEqual(await escrowLamports(), rentReserve.toNumber(), "  Escrow should keep its rent.");
+
+    // With the deposits gone, sells have nothing left to be paid from
+    await expectError(
+      sellLaunch(launchAccounts, await tokenBalance(launchAccounts.destination)),
+      "InvalidSolAmount"
+    );
+    assert.strictEqual(await escrowLamports(), rentReserve.toNumber(), "  Escrow should keep its rent.");
+  });
+
+  it("allowlist phase", async () => {
+    const lamports = (sol: number) => new BN(sol * web3.LAMPORTS_PER_SOL);
+    const allocations = [
+      { wallet: payer, allocation: lamports(0.02) },
+      { wallet: web3.Keypair.generate().publicKey, allocation: lamports(1) },
+    ];
+    // Leaves are keccak(wallet || allocation)
+    const leaves = allocations.map(({ wallet, allocation }) =>
+      Buffer.from(keccak_256(Buffer.concat([wallet.toBuffer(), u64(allocation)])))
+    );
+    const launchAccounts = await createLaunch(
+      tokenName + "l",
+      {
+        allowlist: {
+          root: Array.from(hashPair(leaves[0], leaves[1])),
+          endsAt: new BN(now() + 3600),
+        },
+      },
+      lamports(0.01)
+    );
+    const proof = { allocation: allocations[0].allocation, proof: [Array.from(leaves[1])] };
+
+    await expectError(buyLaunch(launchAccounts, lamports(0.01)), "NotAllowlisted");
+    await expectError(
+      buyLaunch(launchAccounts, lamports(0.01), { ...proof, allocation: lamports(1) }),
+      "NotAllowlisted"
+    );
+
+    await buyLaunch(launchAccounts, lamports(0.01), proof);
+    const buyer = await program.account.buyerInfo.fetch(
+      pda([Buffer.from(BUYER_INFO_SEED), launchAccounts.mint.toBuffer(), payer.toBuffer()])
+    );
+    assert(buyer.solAmount.eq(lamports(0.01)), "  Allowlisted buy should go through.");
+
+    // Purchases add up against the allocation
+    await expectError(buyLaunch(launchAccounts, lamports(0.02), proof), "BuyLimitExceeded");
+  });
+
+  it("batch auction commit, settle and claim", async () => {
+    const unit = new BN(10 ** metadata.decimals);
+    const committed = new BN(0.05 * web3.LAMPORTS_PER_SOL);
+    const endsAt = now() + 8;
+    const launchAccounts = await createLaunch(
+      tokenName + "b",
+      {
+        batchAuction: {
+          endsAt: new BN(endsAt),
+          supply: new BN(100_000_000).mul(unit),
+          maxPrice: new BN(0),
+        },
+      },
+      new BN(0)
+    );
+    const commitment = pda([
+      Buffer.from(AUCTION_COMMITMENT_SEED),
+      launchAccounts.mint.toBuffer(),
+      payer.toBuffer(),
+    ]);
+
+    await program.methods
+      .commitAuction(committed)
+      .accounts({
+        buyer: payer,
+        mint: launchAccounts.mint,
+        escrowAccount: launchAccounts.escrowAccount,
+        tokenPools: launchAccounts.tokenPools,
+        commitment,
+        quoteVault: null,
+        buyerQuoteAccount: null,
+        quoteTokenProgram: null,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const settleContext = {
+      mint: launchAccounts.mint,
+      globalInfo,
+      tokenPools: launchAccounts.tokenPools,
+    };
+    await expectError(
+      program.methods.settleAuction().accounts(settleContext).rpc(),
+      "AuctionNotEnded"
+    );
+    await sleep(Math.max(endsAt - now(), 0) + 2);
+    await program.methods.settleAuction().accounts(settleContext).rpc();
+    let pool = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    assert(pool.auctionSettled, "  Auction should be settled.");
+    assert(pool.auctionSold.gtn(0), "  Auction should clear some supply.");
+    assert(pool.auctionPrice.gtn(0), "  Auction should have a clearing price.");
+
+    const claimerTokenAccount = associatedAddress2022(payer, launchAccounts.mint);
+    const claimContext = {
+      claimer: payer,
+      mint: launchAccounts.mint,
+      tokenVault: launchAccounts.tokenVault,
+      escrowAccount: launchAccounts.escrowAccount,
+      tokenPools: launchAccounts.tokenPools,
+      commitment,
+      claimerTokenAccount,
+      position: null,
+      quoteVault: null,
+      claimerQuoteAccount: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      quoteTokenProgram: null,
+      systemProgram: web3.SystemProgram.programId,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+    await program.methods.claimAuction().accounts(claimContext).rpc();
+
+    // The only participant takes the whole cleared supply
+    pool = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    const received = await tokenBalance(claimerTokenAccount);
+    assert(received.eq(pool.auctionSold), "  Claimer should receive the cleared supply.");
+    assert(pool.auctionClaimed.eq(pool.auctionSold), "  Pool should count the claim.");
+    const state = await program.account.auctionCommitment.fetch(commitment);
+    assert(state.claimed, "  Commitment should be marked claimed.");
+    await expectError(
+      program.methods.claimAuction().accounts(claimContext).rpc(),
+      "AlreadyClaimed"
+    );
+  });
+
+  it("dutch auction sells out into the curve", async () => {
+    const unit = new BN(10 ** metadata.decimals);
+    // At 1000 lamports per whole token every lamport buys exactly a million base units
+    const floorPrice = new BN(1000);
+    const supply = new BN(100_000).mul(unit);
+    const slot = await program.provider.connection.getSlot();
+    const launchAccounts = await createLaunch(
+      tokenName + "d",
+      {
+        dutchAuction: {
+          // Started in the past, the linear decay has already reached the floor
+          startSlot: new BN(Math.max(slot - 10, 0)),
+          startPrice: floorPrice.muln(2),
+          floorPrice,
+          decay: 0,
+          decayRate: floorPrice,
+          supply,
+          endSlot: new BN(slot + 100_000),
+        },
+      },
+      new BN(0)
+    );
+    let pool = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    const tokenReserve = pool.tokenReserve;
+    const solReserve = pool.solReserve;
+
+    await buyLaunch(launchAccounts, new BN(0.01 * web3.LAMPORTS_PER_SOL));
+    pool = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    assert(pool.lastPrice.eq(floorPrice), "  Linear decay should stop at the floor price.");
+    assert(!pool.auctionSettled, "  Auction should still run.");
+    assert(pool.tokenReserve.eq(tokenReserve), "  Auction sales should stay out of the curve.");
+    // Holders exit through the curve once the auction is over
+    await expectError(sellLaunch(launchAccounts, unit), "AuctionNotEnded");
+
+    // Buying exactly what is left sells the auction out
+    const net = supply.sub(pool.auctionSold).mul(floorPrice).div(unit);
+    await buyLaunch(launchAccounts, grossForNet(net, pool.feePercent));
+    pool = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    assert(pool.auctionSettled, "  A sold out auction should end.");
+    assert(pool.auctionSold.eq(supply), "  The whole auction supply should be sold.");
+    assert(pool.tokenReserve.eq(tokenReserve.sub(supply)), "  Sold tokens should leave the curve.");
+    assert(pool.solReserve.eq(solReserve.add(pool.auctionRaised)), "  Proceeds should join the curve.");
+    assert(
+      pool.lastPrice.eq(pool.solReserve.mul(unit).div(pool.tokenReserve)),
+      "  Price should continue from the curve."
+    );
+
+    // Trading continues on the curve
+    const sold = new BN(1000).mul(unit);
+    await sellLaunch(launchAccounts, sold);
+    const after = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    assert(after.tokenReserve.eq(pool.tokenReserve.add(sold)), "  Sells should go into the curve.");
+  });
+
+  it("dutch auction with exponential decay ends at its end slot", async () => {
+    const unit = new BN(10 ** metadata.decimals);
+    const startPrice = new BN(100_000);
+    const floorPrice = new BN(1000);
+    const slot = await program.provider.connection.getSlot();
+    const endSlot = slot + 40;
+    const launchAccounts = await createLaunch(
+      tokenName + "e",
+      {
+        dutchAuction: {
+          startSlot: new BN(slot),
+          startPrice,
+          floorPrice,
+          decay: 1,
+          decayRate: new BN(1000), // 10% per slot
+          supply: new BN(100_000_000).mul(unit),
+          endSlot: new BN(endSlot),
+        },
+      },
+      new BN(0)
+    );
+
+    const amount = new BN(0.01 * web3.LAMPORTS_PER_SOL);
+    await buyLaunch(launchAccounts, amount);
+    let pool = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    assert(
+      pool.lastPrice.lt(startPrice) && pool.lastPrice.gte(floorPrice),
+      "  Price should decay from the start towards the floor."
+    );
+    // The buyer pays the auction price, the curve is untouched
+    const net = amount.sub(amount.muln(pool.feePercent).divn(10000));
+    const buyer = await program.account.buyerInfo.fetch(
+      pda([Buffer.from(BUYER_INFO_SEED), launchAccounts.mint.toBuffer(), payer.toBuffer()])
+    );
+    assert(buyer.tokenAmount.eq(net.mul(unit).div(pool.lastPrice)), "  Buy should fill at the auction price.");
+
+    // Past the end slot the next trade folds the auction into the curve
+    await waitForSlot(endSlot);
+    const sold = buyer.tokenAmount.divn(2);
+    await sellLaunch(launchAccounts, sold);
+    const after = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    assert(after.auctionSettled, "  Auction should end at its end slot.");
+    assert(after.auctionSold.lt(after.auctionSupply), "  Auction should end without selling out.");
+    assert(
+      after.tokenReserve.eq(pool.tokenReserve.sub(pool.auctionSold).add(sold)),
+      "  Sold tokens should leave the curve and the sell should go back into it."
+    );
+  });
+
+  it("transfer-fee launch and harvest", async () => {
+    // A separate creator so the creator and protocol payouts land in different accounts
+    const creator = web3.Keypair.generate();
+    await program.provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: payer,
+          toPubkey: creator.publicKey,
+          lamports: web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const launchAccounts = await createLaunch(
+      tokenName + "f",
+      { transferFee: { basisPoints: 100, maximumFee: new BN("1000000000000000000") } },
+      new BN(0.01 * 10 ** metadata.decimals),
+      { creator }
+    );
+
+    // The vault transfer withholds 1% in the buyer's account
+    const buyerTokenAccount = associatedAddress2022(payer, launchAccounts.mint);
+    await buyLaunch(launchAccounts, new BN(0.05 * web3.LAMPORTS_PER_SOL));
+    const buyer = await program.account.buyerInfo.fetch(
+      pda([Buffer.from(BUYER_INFO_SEED), launchAccounts.mint.toBuffer(), payer.toBuffer()])
+    );
+    assert(
+      (await tokenBalance(buyerTokenAccount)).eq(buyer.tokenAmount),
+      "  Buyer should be credited what arrives after the transfer fee."
+    );
+
+    const creatorBefore = await tokenBalance(launchAccounts.destination);
+    const protocolBefore = await tokenBalance(buyerTokenAccount);
+    await program.methods
+      .harvestTransferFees()
+      .accounts({
+        payer,
+        mint: launchAccounts.mint,
+        feeAuthority: pda([Buffer.from(TRANSFER_FEE_AUTHORITY_SEED), launchAccounts.mint.toBuffer()]),
+        feeVault: pda([Buffer.from(TRANSFER_FEE_VAULT_SEED), launchAccounts.mint.toBuffer()]),
+        globalInfo,
+        tokenPools: launchAccounts.tokenPools,
+        creatorTokenAccount: launchAccounts.destination,
+        protocolTokenAccount: buyerTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // Payouts are made whole, the split follows the pool's creator share
+    const creatorShare = (await tokenBalance(launchAccounts.destination)).sub(creatorBefore);
+    const protocolShare = (await tokenBalance(buyerTokenAccount)).sub(protocolBefore);
+    const pool = await program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    const harvested = creatorShare.add(protocolShare);
+    assert(harvested.gtn(0), "  Withheld fees should be harvested.");
+    assert(
+      creatorShare.eq(harvested.muln(pool.creatorFeeShare).divn(10000)),
+      "  Creator should receive its share of the harvest."
+    );
+  });
+
+  it("SPL-quote launch trades and withdraws through the quote vault", async () => {
+    const adminContext = { globalInfo, admin: payer, systemProgram: web3.SystemProgram.programId };
+    const quoteUnit = new BN(10 ** 6);
+    const quote = await createQuoteMint(6, new BN(1_000_000).mul(quoteUnit));
+    const maxBuyAmount = new BN(100).mul(quoteUnit);
+    await program.methods
+      .addQuoteMint(quote.mint, new BN(20_000).mul(quoteUnit), true, maxBuyAmount)
+      .accounts(adminContext)
+      .rpc();
+    const launchAccounts = await createLaunch(tokenName + "q", {}, new BN(10).mul(quoteUnit), {
+      quote,
+    });
+    const quoteVault = pda([Buffer.from(QUOTE_VAULT_SEED), launchAccounts.mint.toBuffer()]);
+    const quoteAccounts = {
+      escrowAccount: launchAccounts.escrowAccount,
+      globalInfo,
+      tokenPools: launchAccounts.tokenPools,
+      mint: launchAccounts.mint,
+      candles: launchAccounts.candles,
+      position: null,
+      quoteVault,
+      tokenVault: launchAccounts.tokenVault,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      quoteTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+    };
+    const buy = (amount: BN) =>
+      program.methods
+        .buyToken(amount, null)
+        .accounts({
+          ...quoteAccounts,
+          buyer: payer,
+          buyerTokenAccount: launchAccounts.destination,
+          buyerInfo: pda([Buffer.from(BUYER_INFO_SEED), launchAccounts.mint.toBuffer(), payer.toBuffer()]),
+          buyerQuoteAccount: quote.payerAccount,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .rpc();
+    const pool = () => program.account.tokenPools.fetch(launchAccounts.tokenPools);
+    let state = await pool();
+    assert(state.quoteMint.equals(quote.mint), "  Pool should trade against the quote mint.");
+    assert(state.quoteMaxBuyAmount.eq(maxBuyAmount), "  Pool should snapshot the sniper cap.");
+
+    // Buys pay into the quote vault, the escrow holds no quote
+    let vaultBefore = await tokenBalance(quoteVault);
+    await buy(new BN(50).mul(quoteUnit));
+    assert(
+      (await tokenBalance(quoteVault)).sub(vaultBefore).eq(new BN(50).mul(quoteUnit)),
+      "  Buy should pay into the quote vault."
+    );
+
+    // Removing the quote mint does not lift the cap of pools already trading against it
+    await program.methods.removeQuoteMint(quote.mint).accounts(adminContext).rpc();
+    await expectError(buy(new BN(60).mul(quoteUnit)), "BuyLimitExceeded");
+
+    // Sells are paid out of the vault, what leaves it is what leaves the reserve
+    state = await pool();
+    vaultBefore = await tokenBalance(quoteVault);
+    const payerBefore = await tokenBalance(quote.payerAccount);
+    await program.methods
+      .sellToken((await tokenBalance(launchAccounts.destination)).divn(2))
+      .accounts({
+        ...quoteAccounts,
+        seller: payer,
+        sellerTokenAccount: launchAccounts.destination,
+        sellerQuoteAccount: quote.payerAccount,
+      })
+      .rpc();
+    const paidOut = vaultBefore.sub(await tokenBalance(quoteVault));
+    assert(paidOut.gtn(0), "  Sell should pay out of the quote vault.");
+    assert(
+      (await tokenBalance(quote.payerAccount)).sub(payerBefore).eq(paidOut),
+      "  Seller should receive the payout."
+    );
+    assert(state.solReserve.sub((await pool()).solReserve).eq(paidOut), "  Reserve should shrink by the payout.");
+
+    // Collected fees are withdrawn from the vault to the admin's quote account
+    const fees = (await pool()).feesCollected;
+    assert(fees.gtn(0), "  Trades should have collected fees.");
+    vaultBefore = await tokenBalance(quoteVault);
+    const adminBefore = await tokenBalance(quote.payerAccount);
+    await program.methods
+      .withdrawBalance(fees)
+      .accounts({
+        mint: launchAccounts.mint,
+        escrowAccount: launchAccounts.escrowAccount,
+        tokenPools: launchAccounts.tokenPools,
+        globalInfo,
+        quoteVault,
+        adminQuoteAccount: quote.payerAccount,
+        admin: payer,
+        quoteTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    assert(vaultBefore.sub(await tokenBalance(quoteVault)).eq(fees), "  Fees should leave the vault.");
+    assert((await tokenBalance(quote.payerAccount)).sub(adminBefore).eq(fees), "  Admin should receive the fees.");
+    assert((await pool()).feesCollected.eqn(0), "  Pool should have no fees left.");
+  });
 });